@@ -0,0 +1,13 @@
+#version 330 core
+
+layout (location = 0) in vec2 in_pos;
+layout (location = 1) in vec4 in_color;
+
+uniform mat4 view_projection;
+
+out vec4 out_color;
+
+void main() {
+    out_color = in_color;
+    gl_Position = view_projection * vec4(in_pos, 0.0, 1.0);
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+uniform sampler2D sprite_texture;
+
+in vec2 out_uv;
+in vec4 out_color;
+out vec4 FragColor;
+
+void main()
+{
+    FragColor = texture(sprite_texture, out_uv) * out_color;
+}
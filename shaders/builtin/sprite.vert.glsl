@@ -0,0 +1,16 @@
+#version 330 core
+
+layout (location = 0) in vec2 in_pos;
+layout (location = 1) in vec2 in_uv;
+layout (location = 2) in vec4 in_color;
+
+uniform mat4 view_projection;
+
+out vec2 out_uv;
+out vec4 out_color;
+
+void main() {
+    out_uv = in_uv;
+    out_color = in_color;
+    gl_Position = view_projection * vec4(in_pos, 0.0, 1.0);
+}
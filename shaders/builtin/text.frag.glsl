@@ -0,0 +1,13 @@
+#version 330 core
+
+// Glyph atlas, the coverage is stored in the red channel.
+uniform sampler2D sprite_texture;
+
+in vec2 out_uv;
+in vec4 out_color;
+out vec4 FragColor;
+
+void main()
+{
+    FragColor = vec4(out_color.rgb, out_color.a * texture(sprite_texture, out_uv).r);
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+uniform sampler2D main_texture;
+uniform vec4 tint;
+
+in vec2 out_uv;
+out vec4 FragColor;
+
+void main()
+{
+    FragColor = texture(main_texture, out_uv) * tint;
+}
@@ -0,0 +1,14 @@
+#version 330 core
+
+layout (location = 0) in vec2 in_pos;
+layout (location = 2) in vec4 in_color;
+
+uniform mat4 view_projection;
+uniform mat4 model;
+
+out vec4 out_color;
+
+void main() {
+    out_color = in_color;
+    gl_Position = view_projection * model * vec4(in_pos, 0.0, 1.0);
+}
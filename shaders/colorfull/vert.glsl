@@ -4,9 +4,12 @@ layout (location = 0) in vec2 in_pos;
 layout (location = 1) in vec2 in_norm;
 layout (location = 2) in vec2 in_uv;
 
+uniform mat4 view_projection;
+uniform mat4 model;
+
 out vec2 out_uv;
 
 void main() {
     out_uv = in_uv;
-    gl_Position = vec4(in_pos, 1.0, 1.0);
+    gl_Position = view_projection * model * vec4(in_pos, 0.0, 1.0);
 }
\ No newline at end of file
@@ -1,5 +1,13 @@
+use std::{convert::TryFrom, process::ExitCode};
+
 use cacti::core::{
-    world::World,
+    sys::Sys,
+    world::{EntityComponentManager, EntitySelector, World},
+};
+
+use cacti::components::{
+    render_errors::RenderErrors,
+    shader_watcher::ShaderReloaded,
 };
 
 use cacti::systems::{
@@ -8,19 +16,49 @@ use cacti::systems::{
     render_sys::RenderSys,
     after_render_sys::AfterRenderSys,
     procedural_square_sys::ProceduralSquareSys,
+    shader_reload_sys::ShaderReloadSys,
 };
 
 extern crate glfw;
 
+/// Print what the library systems report.
+struct LogSys;
+
+impl Sys for LogSys {
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        if let Some(entity) = <(RenderErrors,)>::query_first_from(world) {
+            for error in world.get_component_mut::<RenderErrors>(&entity).unwrap().take() {
+                eprintln!("Render error: {}", error);
+            }
+        }
+
+        for reloaded in world.read_events::<ShaderReloaded>() {
+            let paths = reloaded.paths.iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
 
-fn main() {
+            match &reloaded.error {
+                None => println!("Reloaded shader ({})", paths),
+                Some(e) => eprintln!("Failed to reload shader ({}): {}", paths, e),
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+
+    let mut world = World::new(64, 64, 64, 7);
+    world.add_system(WindowSys::new("Color Gradient", (800, 600)));
+    world.add_system(ProceduralSquareSys::new().with_hot_reload(true));
+    world.add_system(ShaderReloadSys::default());
 
-    let mut world = World::new(64, 64, 64, 5);
     world.add_system(BeforeRenderSys);
     world.add_system(RenderSys);
     world.add_system(AfterRenderSys);
+    world.add_system(LogSys);
 
-    world.add_system(WindowSys::new("Color Gradient", (800, 600)));
-    world.add_system(ProceduralSquareSys);
-    world.start_loop()
-}
\ No newline at end of file
+    // Returned from main so the world is dropped (and the GL objects deleted) before exiting.
+    let code = world.start_loop();
+    ExitCode::from(u8::try_from(code).unwrap_or(1))
+}
@@ -0,0 +1,401 @@
+
+use std::collections::HashMap;
+
+use glfw::{GamepadAxis, GamepadButton, Key, MouseButton};
+
+use crate::components::{
+    gamepads::Gamepads,
+    input::Input,
+};
+use crate::core::keys::key_from_name;
+
+/// An input that can trigger an action.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Binding {
+    Key(Key),
+    MouseButton(MouseButton),
+    GamepadButton(GamepadButton),
+
+    /// A gamepad axis pushed beyond a threshold. A negative
+    /// threshold means the axis must be pushed below it.
+    GamepadAxis(GamepadAxis, f32),
+}
+
+/// The threshold of the gamepad axes bindings parsed by `Binding::from_name`.
+const AXIS_THRESHOLD: f32 = 0.5;
+
+/// Get a gamepad button from its name (`"A"`, `"Start"`, `"DpadUp"`, ...).
+fn gamepad_button_from_name(name: &str) -> Option<GamepadButton> {
+    Some(match name {
+        "A"           => GamepadButton::ButtonA,
+        "B"           => GamepadButton::ButtonB,
+        "X"           => GamepadButton::ButtonX,
+        "Y"           => GamepadButton::ButtonY,
+        "LeftBumper"  => GamepadButton::ButtonLeftBumper,
+        "RightBumper" => GamepadButton::ButtonRightBumper,
+        "Back"        => GamepadButton::ButtonBack,
+        "Start"       => GamepadButton::ButtonStart,
+        "Guide"       => GamepadButton::ButtonGuide,
+        "LeftThumb"   => GamepadButton::ButtonLeftThumb,
+        "RightThumb"  => GamepadButton::ButtonRightThumb,
+        "DpadUp"      => GamepadButton::ButtonDpadUp,
+        "DpadRight"   => GamepadButton::ButtonDpadRight,
+        "DpadDown"    => GamepadButton::ButtonDpadDown,
+        "DpadLeft"    => GamepadButton::ButtonDpadLeft,
+        _ => return None,
+    })
+}
+
+/// Get a gamepad axis from its name (`"LeftX"`, `"RightTrigger"`, ...).
+fn gamepad_axis_from_name(name: &str) -> Option<GamepadAxis> {
+    Some(match name {
+        "LeftX"        => GamepadAxis::AxisLeftX,
+        "LeftY"        => GamepadAxis::AxisLeftY,
+        "RightX"       => GamepadAxis::AxisRightX,
+        "RightY"       => GamepadAxis::AxisRightY,
+        "LeftTrigger"  => GamepadAxis::AxisLeftTrigger,
+        "RightTrigger" => GamepadAxis::AxisRightTrigger,
+        _ => return None,
+    })
+}
+
+impl Binding {
+    /// Parse a binding from its name.
+    ///
+    /// Keys are written like in `glfw::Key` (`"Space"`, `"LeftShift"`, ...),
+    /// mouse buttons are written `"Mouse:Left"`, `"Mouse:Right"`,
+    /// `"Mouse:Middle"` or `"Mouse:<1-8>"`, gamepad buttons are written
+    /// `"Gamepad:A"`, `"Gamepad:Start"`, `"Gamepad:DpadUp"`, ... and gamepad
+    /// axes are written `"Gamepad:LeftX+"`, `"Gamepad:LeftY-"`, ...
+    /// # Arguments
+    /// * `name` - The name of the binding.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut parts = name.splitn(2, ':');
+
+        match (parts.next()?, parts.next()) {
+            ("Mouse", Some(button)) => {
+                let button = match button {
+                    "Left"   => MouseButton::Button1,
+                    "Right"  => MouseButton::Button2,
+                    "Middle" => MouseButton::Button3,
+                    n => MouseButton::from_i32(n.parse::<i32>().ok()? - 1)?,
+                };
+
+                Some(Binding::MouseButton(button))
+            },
+            ("Gamepad", Some(name)) if name.ends_with('+') || name.ends_with('-') => {
+                let (axis, sign) = name.split_at(name.len() - 1);
+                let threshold = if sign == "+" { AXIS_THRESHOLD } else { -AXIS_THRESHOLD };
+
+                Some(Binding::GamepadAxis(gamepad_axis_from_name(axis)?, threshold))
+            },
+            ("Gamepad", Some(button)) => Some(Binding::GamepadButton(gamepad_button_from_name(button)?)),
+            ("Key", Some(key)) => Some(Binding::Key(key_from_name(key)?)),
+            (key, None) => Some(Binding::Key(key_from_name(key)?)),
+            _ => None,
+        }
+    }
+
+    /// `true` while the binding is held down.
+    fn is_down(&self, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        match (*self, gamepads) {
+            (Binding::Key(key), _) => input.is_key_down(key),
+            (Binding::MouseButton(button), _) => input.is_button_down(button),
+            (Binding::GamepadButton(button), Some(gamepads)) => gamepads.is_button_down(button),
+            (Binding::GamepadAxis(axis, threshold), Some(gamepads)) => {
+                gamepads.iter().any(|p| axis_beyond(gamepads.apply_deadzone(axis, p.raw_axis(axis)), threshold))
+            },
+            _ => false,
+        }
+    }
+
+    /// `true` if the binding was pressed during this frame.
+    fn is_just_pressed(&self, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        match (*self, gamepads) {
+            (Binding::Key(key), _) => input.is_key_just_pressed(key),
+            (Binding::MouseButton(button), _) => input.is_button_just_pressed(button),
+            (Binding::GamepadButton(button), Some(gamepads)) => gamepads.is_button_just_pressed(button),
+            (Binding::GamepadAxis(axis, threshold), Some(gamepads)) => gamepads.iter().any(|p| {
+                axis_beyond(gamepads.apply_deadzone(axis, p.raw_axis(axis)), threshold)
+                    && !axis_beyond(gamepads.apply_deadzone(axis, p.prev_raw_axis(axis)), threshold)
+            }),
+            _ => false,
+        }
+    }
+
+    /// `true` if the binding was released during this frame.
+    fn is_just_released(&self, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        match (*self, gamepads) {
+            (Binding::Key(key), _) => input.is_key_just_released(key),
+            (Binding::MouseButton(button), _) => input.is_button_just_released(button),
+            (Binding::GamepadButton(button), Some(gamepads)) => gamepads.is_button_just_released(button),
+            (Binding::GamepadAxis(axis, threshold), Some(gamepads)) => gamepads.iter().any(|p| {
+                !axis_beyond(gamepads.apply_deadzone(axis, p.raw_axis(axis)), threshold)
+                    && axis_beyond(gamepads.apply_deadzone(axis, p.prev_raw_axis(axis)), threshold)
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// `true` if an axis value is beyond a threshold (below it if the threshold is negative).
+fn axis_beyond(value: f32, threshold: f32) -> bool {
+    if threshold < 0.0 { value <= threshold } else { value >= threshold }
+}
+
+/// An action map error.
+pub struct ActionMapError {
+    /// Kind of error.
+    pub kind: String,
+
+    /// The error description.
+    pub message: String,
+}
+
+impl ActionMapError {
+    /// Create new action map error.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of error.
+    /// * `message` - The error description.
+    pub fn new(kind: &str, message: &str) -> ActionMapError {
+        ActionMapError {
+            kind: String::from(kind),
+            message: String::from(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for ActionMapError {
+    /// Convert an `std::io::Error` to an `ActionMapError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: std::io::Error) -> Self {
+        ActionMapError::new("io", &error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ActionMapError {
+    /// Convert a `serde_json::Error` to an `ActionMapError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: serde_json::Error) -> Self {
+        ActionMapError::new("json", &error.to_string())
+    }
+}
+
+/// Map named actions (`"jump"`, `"fire"`, ...) to the inputs that trigger them.
+pub struct ActionMap {
+    /// The bindings of each action.
+    actions: HashMap<String, Vec<Binding>>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        ActionMap::new()
+    }
+}
+
+impl ActionMap {
+    /// Create new empty `ActionMap`.
+    pub fn new() -> Self {
+        Self {
+            actions: HashMap::new(),
+        }
+    }
+
+    /// Load an action map from a json file.
+    ///
+    /// ```json
+    /// { "jump": ["Space", "Gamepad:A"], "left": ["A", "Left", "Gamepad:LeftX-"], "fire": ["Mouse:Left"] }
+    /// ```
+    /// # Arguments
+    /// * `path` - The path of the json file.
+    pub fn from_json(path: &str) -> Result<Self, ActionMapError> {
+        let src = std::fs::read_to_string(path)?;
+        let actions: HashMap<String, Vec<String>> = serde_json::from_str(&src)?;
+
+        let mut map = ActionMap::new();
+
+        for (action, bindings) in actions {
+            for name in bindings {
+                match Binding::from_name(&name) {
+                    Some(binding) => map.bind(&action, binding),
+                    None => {
+                        let msg = format!("Unknown binding '{}' for action '{}'", name, action);
+                        return Err(ActionMapError::new("Binding", &msg))
+                    },
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Add a binding to an action.
+    /// # Arguments
+    /// * `action` - The action name.
+    /// * `binding` - The input that trigger the action.
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        self.actions.entry(String::from(action)).or_default().push(binding);
+    }
+
+    /// Get the bindings of an action.
+    /// # Arguments
+    /// * `action` - The action name.
+    pub fn get_bindings(&self, action: &str) -> &[Binding] {
+        match self.actions.get(action) {
+            Some(bindings) => bindings,
+            None => &[],
+        }
+    }
+
+    /// `true` while any binding of the action is held down.
+    ///
+    /// The gamepad bindings are ignored without `gamepads`.
+    /// # Arguments
+    /// * `action` - The action name.
+    /// * `input` - The keyboard and mouse state.
+    /// * `gamepads` - The gamepads state, if any.
+    pub fn is_down(&self, action: &str, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        self.get_bindings(action).iter().any(|b| b.is_down(input, gamepads))
+    }
+
+    /// `true` if any binding of the action was pressed during this frame.
+    ///
+    /// The gamepad bindings are ignored without `gamepads`.
+    /// # Arguments
+    /// * `action` - The action name.
+    /// * `input` - The keyboard and mouse state.
+    /// * `gamepads` - The gamepads state, if any.
+    pub fn is_just_pressed(&self, action: &str, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        self.get_bindings(action).iter().any(|b| b.is_just_pressed(input, gamepads))
+    }
+
+    /// `true` if any binding of the action was released during this frame.
+    ///
+    /// The gamepad bindings are ignored without `gamepads`.
+    /// # Arguments
+    /// * `action` - The action name.
+    /// * `input` - The keyboard and mouse state.
+    /// * `gamepads` - The gamepads state, if any.
+    pub fn is_just_released(&self, action: &str, input: &Input, gamepads: Option<&Gamepads>) -> bool {
+        self.get_bindings(action).iter().any(|b| b.is_just_released(input, gamepads))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glfw::{Action, JoystickId, Modifiers};
+    use crate::components::gamepads::{AXIS_COUNT, BUTTON_COUNT};
+    use crate::core::platform_event::PlatformEvent;
+
+    /// Gamepads with a single gamepad connected.
+    fn gamepads() -> Gamepads {
+        let mut gamepads = Gamepads::new(0.2);
+        gamepads.connect(JoystickId::Joystick1, "test");
+        gamepads
+    }
+
+    /// Set the state of the gamepad of `gamepads()`, a single button held down and a single axis value.
+    fn set(gamepads: &mut Gamepads, button: Option<GamepadButton>, axis: (GamepadAxis, f32)) {
+        let mut buttons = [false; BUTTON_COUNT];
+        let mut axes = [0.0; AXIS_COUNT];
+
+        if let Some(button) = button {
+            buttons[button as usize] = true;
+        }
+
+        axes[axis.0 as usize] = axis.1;
+        gamepads.set_state(JoystickId::Joystick1, buttons, axes);
+    }
+
+    fn map() -> ActionMap {
+        let mut map = ActionMap::new();
+        map.bind("jump", Binding::from_name("Space").unwrap());
+        map.bind("jump", Binding::from_name("Gamepad:A").unwrap());
+        map.bind("left", Binding::from_name("Gamepad:LeftX-").unwrap());
+        map
+    }
+
+    #[test]
+    fn parses_binding_names() {
+        assert_eq!(Binding::from_name("Space"), Some(Binding::Key(Key::Space)));
+        assert_eq!(Binding::from_name("Key:Space"), Some(Binding::Key(Key::Space)));
+        assert_eq!(Binding::from_name("Mouse:Right"), Some(Binding::MouseButton(MouseButton::Button2)));
+        assert_eq!(Binding::from_name("Mouse:4"), Some(Binding::MouseButton(MouseButton::Button4)));
+        assert_eq!(Binding::from_name("Gamepad:Start"), Some(Binding::GamepadButton(GamepadButton::ButtonStart)));
+        assert_eq!(Binding::from_name("Gamepad:RightY+"), Some(Binding::GamepadAxis(GamepadAxis::AxisRightY, AXIS_THRESHOLD)));
+        assert_eq!(Binding::from_name("Gamepad:LeftX-"), Some(Binding::GamepadAxis(GamepadAxis::AxisLeftX, -AXIS_THRESHOLD)));
+        assert_eq!(Binding::from_name("Gamepad:Z"), None);
+        assert_eq!(Binding::from_name("Mouse:9"), None);
+    }
+
+    #[test]
+    fn gamepad_button_edges() {
+        let map = map();
+        let input = Input::new();
+        let mut gamepads = gamepads();
+
+        set(&mut gamepads, Some(GamepadButton::ButtonA), (GamepadAxis::AxisLeftX, 0.0));
+        assert!(map.is_down("jump", &input, Some(&gamepads)));
+        assert!(map.is_just_pressed("jump", &input, Some(&gamepads)));
+        assert!(!map.is_just_released("jump", &input, Some(&gamepads)));
+
+        set(&mut gamepads, Some(GamepadButton::ButtonA), (GamepadAxis::AxisLeftX, 0.0));
+        assert!(map.is_down("jump", &input, Some(&gamepads)));
+        assert!(!map.is_just_pressed("jump", &input, Some(&gamepads)));
+
+        set(&mut gamepads, None, (GamepadAxis::AxisLeftX, 0.0));
+        assert!(!map.is_down("jump", &input, Some(&gamepads)));
+        assert!(map.is_just_released("jump", &input, Some(&gamepads)));
+    }
+
+    #[test]
+    fn gamepad_axis_edges() {
+        let map = map();
+        let input = Input::new();
+        let mut gamepads = gamepads();
+
+        // Pushed the wrong way.
+        set(&mut gamepads, None, (GamepadAxis::AxisLeftX, 0.9));
+        assert!(!map.is_down("left", &input, Some(&gamepads)));
+
+        set(&mut gamepads, None, (GamepadAxis::AxisLeftX, -0.9));
+        assert!(map.is_down("left", &input, Some(&gamepads)));
+        assert!(map.is_just_pressed("left", &input, Some(&gamepads)));
+
+        set(&mut gamepads, None, (GamepadAxis::AxisLeftX, -0.8));
+        assert!(map.is_down("left", &input, Some(&gamepads)));
+        assert!(!map.is_just_pressed("left", &input, Some(&gamepads)));
+
+        // Beyond the threshold raw, but not once the deadzone is applied.
+        set(&mut gamepads, None, (GamepadAxis::AxisLeftX, -0.55));
+        assert!(!map.is_down("left", &input, Some(&gamepads)));
+        assert!(map.is_just_released("left", &input, Some(&gamepads)));
+    }
+
+    #[test]
+    fn keyboard_only_ignores_gamepads() {
+        let map = map();
+        let mut input = Input::new();
+        let mut gamepads = gamepads();
+
+        set(&mut gamepads, Some(GamepadButton::ButtonA), (GamepadAxis::AxisLeftX, -1.0));
+        assert!(!map.is_down("jump", &input, None));
+        assert!(!map.is_down("left", &input, None));
+
+        input.apply(&PlatformEvent::Key { key: Key::Space, scancode: 0, action: Action::Press, mods: Modifiers::empty() });
+        assert!(map.is_down("jump", &input, None));
+        assert!(map.is_just_pressed("jump", &input, None));
+        assert!(map.is_down("jump", &input, Some(&Gamepads::new(0.2))));
+
+        input.begin_frame();
+        input.apply(&PlatformEvent::Key { key: Key::Space, scancode: 0, action: Action::Release, mods: Modifiers::empty() });
+        assert!(map.is_just_released("jump", &input, None));
+        assert!(!map.is_down("unknown", &input, None));
+    }
+}
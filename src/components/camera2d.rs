@@ -0,0 +1,194 @@
+
+use cgmath::{
+    Matrix4,
+    Rad,
+    Vector2,
+    Vector3,
+};
+
+use crate::components::color::Color;
+use crate::core::{
+    entity::Entity,
+    rect::Rect,
+};
+
+/// An orthographic camera.
+///
+/// At zoom `1.0` one world unit is one pixel of the render target.
+pub struct Camera2D {
+    /// The world position seen at the center of the viewport.
+    pub position: Vector2<f32>,
+
+    /// The zoom factor, greater values make things bigger.
+    pub zoom: f32,
+
+    /// The rotation of the camera in radians.
+    pub rotation: f32,
+
+    /// The area of the render target covered by the camera,
+    /// from `(0, 0)` (bottom left) to `(1, 1)` (top right).
+    pub viewport: Rect,
+
+    /// The color used to clear the viewport before drawing.
+    pub clear_color: Color,
+
+    /// Cameras are drawn by increasing order.
+    pub order: i32,
+
+    /// The window entity the camera draws into, `None` for the primary window.
+    pub target: Option<Entity>,
+}
+
+impl Camera2D {
+    /// Create new `Camera2D` that covers the whole render target.
+    /// # Arguments
+    /// * `position` - The world position seen at the center of the viewport.
+    pub fn new(position: Vector2<f32>) -> Self {
+        Self {
+            position,
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport: Rect::new(0.0, 0.0, 1.0, 1.0),
+            clear_color: Color(0.15, 0.15, 0.15, 1.0),
+            order: 0,
+            target: None,
+        }
+    }
+
+    /// The viewport of the camera in pixels.
+    /// # Arguments
+    /// * `target_size` - The size of the render target in pixels.
+    pub fn viewport_pixels(&self, target_size: (u32, u32)) -> Rect {
+        let (w, h) = (target_size.0 as f32, target_size.1 as f32);
+        Rect::new(self.viewport.x * w, self.viewport.y * h, self.viewport.w * w, self.viewport.h * h)
+    }
+
+    /// The view matrix (world space to camera space).
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::from_angle_z(Rad(-self.rotation)) * Matrix4::from_translation(Vector3::new(-self.position.x, -self.position.y, 0.0))
+    }
+
+    /// The projection matrix (camera space to clip space).
+    /// # Arguments
+    /// * `target_size` - The size of the render target in pixels.
+    pub fn projection(&self, target_size: (u32, u32)) -> Matrix4<f32> {
+        let viewport = self.viewport_pixels(target_size);
+        let half_w = viewport.w * 0.5 / self.zoom;
+        let half_h = viewport.h * 0.5 / self.zoom;
+
+        cgmath::ortho(-half_w, half_w, -half_h, half_h, -1.0, 1.0)
+    }
+
+    /// The view projection matrix (world space to clip space).
+    /// # Arguments
+    /// * `target_size` - The size of the render target in pixels.
+    pub fn view_projection(&self, target_size: (u32, u32)) -> Matrix4<f32> {
+        self.projection(target_size) * self.view()
+    }
+
+    /// Convert a screen position into a world position.
+    /// # Arguments
+    /// * `screen` - The position in pixels from the top left corner of the render target.
+    /// * `target_size` - The size of the render target in pixels.
+    pub fn screen_to_world(&self, screen: Vector2<f32>, target_size: (u32, u32)) -> Vector2<f32> {
+        let viewport = self.viewport_pixels(target_size);
+
+        // Position relative to the viewport center, y up.
+        let x = (screen.x - viewport.x - viewport.w * 0.5) / self.zoom;
+        let y = (target_size.1 as f32 - screen.y - viewport.y - viewport.h * 0.5) / self.zoom;
+
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector2::new(x * cos - y * sin, x * sin + y * cos) + self.position
+    }
+
+    /// Convert a world position into a screen position.
+    /// # Arguments
+    /// * `world` - The world position.
+    /// * `target_size` - The size of the render target in pixels.
+    pub fn world_to_screen(&self, world: Vector2<f32>, target_size: (u32, u32)) -> Vector2<f32> {
+        let viewport = self.viewport_pixels(target_size);
+        let local = world - self.position;
+
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let x = (local.x * cos - local.y * sin) * self.zoom;
+        let y = (local.x * sin + local.y * cos) * self.zoom;
+
+        Vector2::new(
+            x + viewport.x + viewport.w * 0.5,
+            target_size.1 as f32 - (y + viewport.y + viewport.h * 0.5),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{InnerSpace, Vector4};
+
+    const TARGET: (u32, u32) = (800, 600);
+
+    fn assert_near(a: Vector2<f32>, b: Vector2<f32>) {
+        assert!((a - b).magnitude() < 1e-3, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn screen_center_is_the_camera_position() {
+        let camera = Camera2D::new(Vector2::new(10.0, -5.0));
+
+        assert_near(camera.screen_to_world(Vector2::new(400.0, 300.0), TARGET), Vector2::new(10.0, -5.0));
+        assert_near(camera.world_to_screen(Vector2::new(10.0, -5.0), TARGET), Vector2::new(400.0, 300.0));
+
+        // The screen y goes down, the world y goes up.
+        assert_near(camera.screen_to_world(Vector2::new(0.0, 0.0), TARGET), Vector2::new(-390.0, 295.0));
+    }
+
+    #[test]
+    fn zoom_scales_around_the_center() {
+        let mut camera = Camera2D::new(Vector2::new(0.0, 0.0));
+        camera.zoom = 2.0;
+
+        assert_near(camera.screen_to_world(Vector2::new(500.0, 200.0), TARGET), Vector2::new(50.0, 50.0));
+        assert_near(camera.world_to_screen(Vector2::new(50.0, 50.0), TARGET), Vector2::new(500.0, 200.0));
+    }
+
+    #[test]
+    fn offset_viewport_is_centered_on_its_own_area() {
+        let mut camera = Camera2D::new(Vector2::new(0.0, 0.0));
+        camera.viewport = Rect::new(0.5, 0.0, 0.5, 0.5);
+
+        // The center of the bottom right quarter of the window.
+        assert_near(camera.world_to_screen(Vector2::new(0.0, 0.0), TARGET), Vector2::new(600.0, 450.0));
+        assert_near(camera.screen_to_world(Vector2::new(700.0, 450.0), TARGET), Vector2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let mut camera = Camera2D::new(Vector2::new(-30.0, 12.5));
+        camera.zoom = 0.75;
+        camera.rotation = 0.6;
+        camera.viewport = Rect::new(0.25, 0.1, 0.5, 0.8);
+
+        for screen in [Vector2::new(0.0, 0.0), Vector2::new(123.0, 456.0), Vector2::new(800.0, 600.0)].iter() {
+            let world = camera.screen_to_world(*screen, TARGET);
+            assert_near(camera.world_to_screen(world, TARGET), *screen);
+        }
+    }
+
+    #[test]
+    fn world_to_screen_matches_the_view_projection() {
+        let mut camera = Camera2D::new(Vector2::new(5.0, 7.0));
+        camera.zoom = 1.5;
+        camera.rotation = -0.3;
+        camera.viewport = Rect::new(0.5, 0.5, 0.5, 0.5);
+
+        let world = Vector2::new(40.0, -25.0);
+        let clip = camera.view_projection(TARGET) * Vector4::new(world.x, world.y, 0.0, 1.0);
+
+        // Clip space to the pixels of the viewport, y down.
+        let viewport = camera.viewport_pixels(TARGET);
+        let x = viewport.x + (clip.x + 1.0) * 0.5 * viewport.w;
+        let y = TARGET.1 as f32 - (viewport.y + (clip.y + 1.0) * 0.5 * viewport.h);
+
+        assert_near(camera.world_to_screen(world, TARGET), Vector2::new(x, y));
+    }
+}
@@ -1,2 +1,3 @@
 /// Represent RGBA color.
+#[derive(Clone, Copy)]
 pub struct Color(pub f32, pub f32, pub f32, pub f32);
\ No newline at end of file
@@ -0,0 +1,412 @@
+use std::{f32::consts::PI, time::Instant};
+
+use cgmath::{
+    InnerSpace,
+    Vector2,
+    Vector4,
+};
+
+use crate::components::{
+    color::Color,
+    mesh_2d::Mesh2D,
+};
+use crate::core::{
+    gl::builtin::LineVertex,
+    rect::Rect,
+};
+
+/// `false` in release builds, where debug drawing is compiled out.
+pub const ENABLED: bool = cfg!(debug_assertions);
+
+/// The amount of segments of the debug circles.
+const CIRCLE_SEGMENTS: u32 = 32;
+
+/// The height of the capital letters in font units, the glyphs are 4 units wide.
+const GLYPH_HEIGHT: f32 = 6.0;
+
+/// The horizontal distance between two characters, in font units.
+const GLYPH_ADVANCE: f32 = 6.0;
+
+/// The vertical distance between two lines of text, in font units.
+const LINE_ADVANCE: f32 = 9.0;
+
+/// The strokes of a character in the debug font, polylines with the baseline at `y = 0`.
+///
+/// Lowercase letters are drawn as capitals, unknown characters as `?`.
+fn glyph(c: char) -> &'static [&'static [(i8, i8)]] {
+    match c.to_ascii_uppercase() {
+        ' ' => &[],
+        'A' => &[&[(0, 0), (0, 4), (2, 6), (4, 4), (4, 0)], &[(0, 3), (4, 3)]],
+        'B' => &[&[(0, 0), (0, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)], &[(3, 3), (4, 2), (4, 1), (3, 0), (0, 0)]],
+        'C' => &[&[(4, 6), (0, 6), (0, 0), (4, 0)]],
+        'D' => &[&[(0, 0), (0, 6), (3, 6), (4, 5), (4, 1), (3, 0), (0, 0)]],
+        'E' => &[&[(4, 6), (0, 6), (0, 0), (4, 0)], &[(0, 3), (3, 3)]],
+        'F' => &[&[(4, 6), (0, 6), (0, 0)], &[(0, 3), (3, 3)]],
+        'G' => &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (2, 3)]],
+        'H' => &[&[(0, 0), (0, 6)], &[(4, 0), (4, 6)], &[(0, 3), (4, 3)]],
+        'I' => &[&[(1, 6), (3, 6)], &[(2, 6), (2, 0)], &[(1, 0), (3, 0)]],
+        'J' => &[&[(4, 6), (4, 0), (0, 0), (0, 2)]],
+        'K' => &[&[(0, 0), (0, 6)], &[(4, 6), (0, 3), (4, 0)]],
+        'L' => &[&[(0, 6), (0, 0), (4, 0)]],
+        'M' => &[&[(0, 0), (0, 6), (2, 3), (4, 6), (4, 0)]],
+        'N' => &[&[(0, 0), (0, 6), (4, 0), (4, 6)]],
+        'O' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)]],
+        'P' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3)]],
+        'Q' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(2, 2), (4, 0)]],
+        'R' => &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3), (4, 0)]],
+        'S' => &[&[(4, 6), (1, 6), (0, 5), (0, 4), (1, 3), (3, 3), (4, 2), (4, 1), (3, 0), (0, 0)]],
+        'T' => &[&[(0, 6), (4, 6)], &[(2, 6), (2, 0)]],
+        'U' => &[&[(0, 6), (0, 0), (4, 0), (4, 6)]],
+        'V' => &[&[(0, 6), (2, 0), (4, 6)]],
+        'W' => &[&[(0, 6), (1, 0), (2, 3), (3, 0), (4, 6)]],
+        'X' => &[&[(0, 0), (4, 6)], &[(0, 6), (4, 0)]],
+        'Y' => &[&[(0, 6), (2, 3), (4, 6)], &[(2, 3), (2, 0)]],
+        'Z' => &[&[(0, 6), (4, 6), (0, 0), (4, 0)]],
+        '0' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(0, 0), (4, 6)]],
+        '1' => &[&[(1, 5), (2, 6), (2, 0)], &[(1, 0), (3, 0)]],
+        '2' => &[&[(0, 6), (4, 6), (4, 3), (0, 3), (0, 0), (4, 0)]],
+        '3' => &[&[(0, 6), (4, 6), (4, 0), (0, 0)], &[(1, 3), (4, 3)]],
+        '4' => &[&[(0, 6), (0, 3), (4, 3)], &[(4, 6), (4, 0)]],
+        '5' => &[&[(4, 6), (0, 6), (0, 3), (4, 3), (4, 0), (0, 0)]],
+        '6' => &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (0, 3)]],
+        '7' => &[&[(0, 6), (4, 6), (2, 0)]],
+        '8' => &[&[(0, 0), (0, 6), (4, 6), (4, 0), (0, 0)], &[(0, 3), (4, 3)]],
+        '9' => &[&[(4, 3), (0, 3), (0, 6), (4, 6), (4, 0), (0, 0)]],
+        '.' => &[&[(2, 0), (2, 1)]],
+        ',' => &[&[(2, 1), (1, -1)]],
+        ':' => &[&[(2, 1), (2, 2)], &[(2, 4), (2, 5)]],
+        ';' => &[&[(2, 4), (2, 5)], &[(2, 1), (1, -1)]],
+        '-' => &[&[(1, 3), (3, 3)]],
+        '+' => &[&[(0, 3), (4, 3)], &[(2, 1), (2, 5)]],
+        '=' => &[&[(0, 2), (4, 2)], &[(0, 4), (4, 4)]],
+        '*' => &[&[(0, 1), (4, 5)], &[(0, 5), (4, 1)], &[(2, 1), (2, 5)]],
+        '/' => &[&[(0, 0), (4, 6)]],
+        '\\' => &[&[(0, 6), (4, 0)]],
+        '%' => &[&[(0, 0), (4, 6)], &[(0, 6), (0, 5)], &[(4, 1), (4, 0)]],
+        '(' => &[&[(3, 6), (1, 4), (1, 2), (3, 0)]],
+        ')' => &[&[(1, 6), (3, 4), (3, 2), (1, 0)]],
+        '[' => &[&[(3, 6), (1, 6), (1, 0), (3, 0)]],
+        ']' => &[&[(1, 6), (3, 6), (3, 0), (1, 0)]],
+        '<' => &[&[(4, 5), (0, 3), (4, 1)]],
+        '>' => &[&[(0, 5), (4, 3), (0, 1)]],
+        '#' => &[&[(1, 0), (1, 6)], &[(3, 0), (3, 6)], &[(0, 2), (4, 2)], &[(0, 4), (4, 4)]],
+        '!' => &[&[(2, 6), (2, 2)], &[(2, 0), (2, 1)]],
+        '_' => &[&[(0, 0), (4, 0)]],
+        '\'' => &[&[(2, 6), (2, 4)]],
+        '"' => &[&[(1, 6), (1, 4)], &[(3, 6), (3, 4)]],
+        // `?` and the unknown characters.
+        _ => &[&[(0, 5), (0, 6), (4, 6), (4, 3), (2, 3), (2, 2)], &[(2, 0), (2, 1)]],
+    }
+}
+
+/// A line drawn by `DebugDraw`.
+struct DebugLine {
+    a: Vector2<f32>,
+    b: Vector2<f32>,
+    color: Color,
+
+    /// The time (in seconds) the line is still drawn, `0.0` for a single frame.
+    remaining: f32,
+}
+
+/// Shapes drawn on top of everything for debugging, in world coordinates.
+///
+/// Systems push shapes each frame, `DebugDrawSys` draws them after `RenderSys`
+/// with the cameras of the primary window. A shape without duration is drawn
+/// for a single frame. In release builds nothing is pushed nor drawn.
+///
+/// Text is drawn with lines too, in a built-in font of the ASCII
+/// letters, digits and usual punctuation, so it needs no font atlas.
+///
+/// ```ignore
+/// let entity = <(DebugDraw,)>::query_first_from(world).unwrap();
+/// let debug = world.get_component_mut::<DebugDraw>(&entity).unwrap();
+/// debug.arrow(pos, pos + velocity, Color(1.0, 0.0, 0.0, 1.0), None);
+/// ```
+pub struct DebugDraw {
+    /// `false` to stop drawing, the shapes are still accepted and expire.
+    pub enabled: bool,
+
+    /// The lines waiting to be drawn.
+    lines: Vec<DebugLine>,
+
+    /// When the lines were drawn for the last time.
+    last_flush: Option<Instant>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        DebugDraw::new()
+    }
+}
+
+impl DebugDraw {
+    /// Create new enabled `DebugDraw` without shapes.
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            lines: Vec::new(),
+            last_flush: None,
+        }
+    }
+
+    /// Draw a line.
+    /// # Arguments
+    /// * `a` - The start of the line.
+    /// * `b` - The end of the line.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the line is drawn, a single frame if `None`.
+    pub fn line(&mut self, a: Vector2<f32>, b: Vector2<f32>, color: Color, duration: Option<f32>) {
+        if !ENABLED {
+            return
+        }
+
+        self.lines.push(DebugLine { a, b, color, remaining: duration.unwrap_or(0.0) });
+    }
+
+    /// Draw a closed outline.
+    fn outline(&mut self, points: &[Vector2<f32>], color: Color, duration: Option<f32>) {
+        for i in 0..points.len() {
+            self.line(points[i], points[(i + 1) % points.len()], color, duration);
+        }
+    }
+
+    /// Draw the outline of a rectangle.
+    /// # Arguments
+    /// * `rect` - The rectangle.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the rectangle is drawn, a single frame if `None`.
+    pub fn rect(&mut self, rect: &Rect, color: Color, duration: Option<f32>) {
+        let (min, max) = (rect.min(), rect.max());
+        let corners = [min, Vector2::new(max.x, min.y), max, Vector2::new(min.x, max.y)];
+
+        self.outline(&corners, color, duration);
+    }
+
+    /// Draw the outline of a circle.
+    /// # Arguments
+    /// * `center` - The circle center.
+    /// * `radius` - The circle radius.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the circle is drawn, a single frame if `None`.
+    pub fn circle(&mut self, center: Vector2<f32>, radius: f32, color: Color, duration: Option<f32>) {
+        if !ENABLED {
+            return
+        }
+
+        let points = (0..CIRCLE_SEGMENTS).map(|i| {
+            let angle = 2.0 * PI * i as f32 / CIRCLE_SEGMENTS as f32;
+            center + Vector2::new(angle.cos(), angle.sin()) * radius
+        }).collect::<Vec<Vector2<f32>>>();
+
+        self.outline(&points, color, duration);
+    }
+
+    /// Draw an arrow.
+    /// # Arguments
+    /// * `from` - The tail of the arrow.
+    /// * `to` - The head of the arrow.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the arrow is drawn, a single frame if `None`.
+    pub fn arrow(&mut self, from: Vector2<f32>, to: Vector2<f32>, color: Color, duration: Option<f32>) {
+        self.line(from, to, color, duration);
+
+        let dir = to - from;
+
+        if dir.magnitude2() == 0.0 {
+            return
+        }
+
+        // The head is a fifth of the arrow, its sides are 30° off the arrow.
+        let back = -dir * 0.2;
+        let (sin, cos) = (PI / 6.0).sin_cos();
+
+        for side in [-1.0, 1.0] {
+            let s = sin * side;
+            let head = Vector2::new(back.x * cos - back.y * s, back.x * s + back.y * cos);
+            self.line(to, to + head, color, duration);
+        }
+    }
+
+    /// Draw a cross (`x`) to mark a point.
+    /// # Arguments
+    /// * `center` - The marked point.
+    /// * `size` - The width and height of the cross.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the cross is drawn, a single frame if `None`.
+    pub fn cross(&mut self, center: Vector2<f32>, size: f32, color: Color, duration: Option<f32>) {
+        let h = size / 2.0;
+
+        self.line(center + Vector2::new(-h, -h), center + Vector2::new(h, h), color, duration);
+        self.line(center + Vector2::new(-h, h), center + Vector2::new(h, -h), color, duration);
+    }
+
+    /// Draw text, one line per `\n`.
+    /// # Arguments
+    /// * `pos` - The bottom left corner of the first line.
+    /// * `text` - The text.
+    /// * `size` - The height of the capital letters.
+    /// * `color` - The line color.
+    /// * `duration` - The time (in seconds) the text is drawn, a single frame if `None`.
+    pub fn text(&mut self, pos: Vector2<f32>, text: &str, size: f32, color: Color, duration: Option<f32>) {
+        if !ENABLED {
+            return
+        }
+
+        let unit = size / GLYPH_HEIGHT;
+
+        for (row, line) in text.lines().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                let origin = pos + Vector2::new(column as f32 * GLYPH_ADVANCE, -(row as f32) * LINE_ADVANCE) * unit;
+                let point = |(x, y): (i8, i8)| origin + Vector2::new(x as f32, y as f32) * unit;
+
+                for stroke in glyph(c) {
+                    for segment in stroke.windows(2) {
+                        self.line(point(segment[0]), point(segment[1]), color, duration);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove all the shapes, even the ones with a duration.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// The amount of lines waiting to be drawn.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The vertices of the lines to draw, two per line.
+    pub fn vertices(&self) -> Vec<LineVertex> {
+        self.lines.iter().flat_map(|l| {
+            let Color(r, g, b, a) = l.color;
+            let color = Vector4::new(r, g, b, a);
+
+            [LineVertex::new(l.a, color), LineVertex::new(l.b, color)]
+        }).collect()
+    }
+
+    /// Remove the lines that expired, called by `DebugDrawSys` once the lines are drawn.
+    pub fn expire(&mut self) {
+        let now = Instant::now();
+        let delta = self.last_flush.map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last_flush = Some(now);
+
+        self.lines.retain_mut(|l| {
+            l.remaining -= delta;
+            l.remaining > 0.0
+        });
+    }
+}
+
+/// The GPU mesh of the `DebugDraw` lines, created by `DebugDrawSys`.
+pub struct DebugDrawMesh(pub Mesh2D);
+
+// Nothing is pushed in release builds.
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+
+    const WHITE: Color = Color(1.0, 1.0, 1.0, 1.0);
+
+    /// The two ends of each line.
+    fn lines(debug: &DebugDraw) -> Vec<(Vector2<f32>, Vector2<f32>)> {
+        debug.vertices().chunks(2).map(|v| (v[0].pos, v[1].pos)).collect()
+    }
+
+    fn assert_near(a: Vector2<f32>, b: Vector2<f32>) {
+        assert!((a - b).magnitude() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn rect_is_a_closed_outline() {
+        let mut debug = DebugDraw::new();
+        debug.rect(&Rect::new(1.0, 2.0, 3.0, 4.0), WHITE, None);
+
+        assert_eq!(lines(&debug), vec![
+            (Vector2::new(1.0, 2.0), Vector2::new(4.0, 2.0)),
+            (Vector2::new(4.0, 2.0), Vector2::new(4.0, 6.0)),
+            (Vector2::new(4.0, 6.0), Vector2::new(1.0, 6.0)),
+            (Vector2::new(1.0, 6.0), Vector2::new(1.0, 2.0)),
+        ]);
+    }
+
+    #[test]
+    fn circle_points_are_on_the_circle() {
+        let mut debug = DebugDraw::new();
+        debug.circle(Vector2::new(5.0, -5.0), 2.0, WHITE, None);
+
+        let lines = lines(&debug);
+        assert_eq!(lines.len(), CIRCLE_SEGMENTS as usize);
+        assert_near(lines[0].0, Vector2::new(7.0, -5.0));
+
+        for (i, (a, b)) in lines.iter().enumerate() {
+            assert!(((a - Vector2::new(5.0, -5.0)).magnitude() - 2.0).abs() < 1e-4);
+            assert_eq!(*b, lines[(i + 1) % lines.len()].0);
+        }
+    }
+
+    #[test]
+    fn arrow_has_a_head_at_its_end() {
+        let mut debug = DebugDraw::new();
+        debug.arrow(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0), WHITE, None);
+
+        let lines = lines(&debug);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], (Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0)));
+
+        // Both sides of the head go back a fifth of the arrow, 30° off it.
+        let (sin, cos) = (PI / 6.0).sin_cos();
+        assert_eq!(lines[1].0, Vector2::new(10.0, 0.0));
+        assert_near(lines[1].1, Vector2::new(10.0 - 2.0 * cos, 2.0 * sin));
+        assert_near(lines[2].1, Vector2::new(10.0 - 2.0 * cos, -2.0 * sin));
+
+        // Without length there is no head.
+        debug.clear();
+        debug.arrow(Vector2::new(1.0, 1.0), Vector2::new(1.0, 1.0), WHITE, None);
+        assert_eq!(debug.line_count(), 1);
+    }
+
+    #[test]
+    fn cross_is_two_diagonals() {
+        let mut debug = DebugDraw::new();
+        debug.cross(Vector2::new(1.0, 1.0), 2.0, WHITE, None);
+
+        assert_eq!(lines(&debug), vec![
+            (Vector2::new(0.0, 0.0), Vector2::new(2.0, 2.0)),
+            (Vector2::new(0.0, 2.0), Vector2::new(2.0, 0.0)),
+        ]);
+    }
+
+    #[test]
+    fn vertices_carry_the_line_color() {
+        let mut debug = DebugDraw::new();
+        debug.line(Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Color(0.1, 0.2, 0.3, 0.4), None);
+        debug.line(Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0), WHITE, Some(1.0));
+
+        let vertices = debug.vertices();
+        assert_eq!(vertices.len(), 4);
+        assert_eq!(vertices[1].color, Vector4::new(0.1, 0.2, 0.3, 0.4));
+        assert_eq!(vertices[3].color, Vector4::new(1.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn text_is_laid_out_in_lines() {
+        let mut debug = DebugDraw::new();
+        debug.text(Vector2::new(10.0, 20.0), "L1\n-", 12.0, WHITE, None);
+
+        // `L` is 2 lines, `1` is 3 and `-` is 1, in font units of 2 world units.
+        let lines = lines(&debug);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], (Vector2::new(10.0, 32.0), Vector2::new(10.0, 20.0)));
+        assert_eq!(lines[2].0, Vector2::new(24.0, 30.0));
+        assert_eq!(lines[5], (Vector2::new(12.0, 8.0), Vector2::new(16.0, 8.0)));
+
+        // Lowercase letters are capitals, unknown characters a question mark.
+        assert_eq!(glyph('l'), glyph('L'));
+        assert_eq!(glyph('€'), glyph('?'));
+    }
+}
@@ -0,0 +1,244 @@
+
+use glfw::{GamepadAxis, GamepadButton, JoystickId};
+
+/// The amount of buttons of a gamepad.
+pub const BUTTON_COUNT: usize = 15;
+
+/// The amount of axes of a gamepad.
+pub const AXIS_COUNT: usize = 6;
+
+/// Sent when a gamepad is connected or disconnected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GamepadEvent {
+    Connected(JoystickId),
+    Disconnected(JoystickId),
+}
+
+/// The state of a connected gamepad.
+pub struct Gamepad {
+    /// The joystick slot of the gamepad.
+    pub id: JoystickId,
+
+    /// The name of the gamepad mapping.
+    pub name: String,
+
+    /// The buttons state of this frame.
+    buttons: [bool; BUTTON_COUNT],
+
+    /// The buttons state of the previous frame.
+    prev_buttons: [bool; BUTTON_COUNT],
+
+    /// The raw axes values of this frame.
+    axes: [f32; AXIS_COUNT],
+
+    /// The raw axes values of the previous frame.
+    prev_axes: [f32; AXIS_COUNT],
+}
+
+impl Gamepad {
+    /// `true` while the button is held down.
+    pub fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.buttons[button as usize]
+    }
+
+    /// `true` if the button was pressed during this frame.
+    pub fn is_button_just_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons[button as usize] && !self.prev_buttons[button as usize]
+    }
+
+    /// `true` if the button was released during this frame.
+    pub fn is_button_just_released(&self, button: GamepadButton) -> bool {
+        !self.buttons[button as usize] && self.prev_buttons[button as usize]
+    }
+
+    /// The raw value of an axis, from `-1.0` to `1.0`.
+    pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes[axis as usize]
+    }
+
+    /// The raw value of an axis during the previous frame.
+    pub fn prev_raw_axis(&self, axis: GamepadAxis) -> f32 {
+        self.prev_axes[axis as usize]
+    }
+}
+
+/// All the connected gamepads.
+///
+/// Updated once per frame by `GamepadSys`, or fed by hand
+/// with `connect`, `set_state` and `disconnect`.
+pub struct Gamepads {
+    /// The connected gamepads.
+    pads: Vec<Gamepad>,
+
+    /// Stick values under this threshold are read as `0.0`.
+    pub deadzone: f32,
+}
+
+impl Gamepads {
+    /// Create new `Gamepads` with no gamepad connected.
+    /// # Arguments
+    /// * `deadzone` - Stick values under this threshold are read as `0.0`.
+    pub fn new(deadzone: f32) -> Self {
+        Self {
+            pads: Vec::new(),
+            deadzone,
+        }
+    }
+
+    /// Add a gamepad.
+    ///
+    /// Returns a `GamepadEvent::Connected` if the gamepad wasn't already connected.
+    /// # Arguments
+    /// * `id` - The joystick slot of the gamepad.
+    /// * `name` - The name of the gamepad mapping.
+    pub fn connect(&mut self, id: JoystickId, name: &str) -> Option<GamepadEvent> {
+        if self.get(id).is_some() {
+            return None
+        }
+
+        self.pads.push(Gamepad {
+            id,
+            name: String::from(name),
+            buttons: [false; BUTTON_COUNT],
+            prev_buttons: [false; BUTTON_COUNT],
+            axes: [0.0; AXIS_COUNT],
+            prev_axes: [0.0; AXIS_COUNT],
+        });
+
+        Some(GamepadEvent::Connected(id))
+    }
+
+    /// Remove a gamepad.
+    ///
+    /// Returns a `GamepadEvent::Disconnected` if the gamepad was connected.
+    /// # Arguments
+    /// * `id` - The joystick slot of the gamepad.
+    pub fn disconnect(&mut self, id: JoystickId) -> Option<GamepadEvent> {
+        let index = self.pads.iter().position(|p| p.id == id)?;
+        self.pads.remove(index);
+
+        Some(GamepadEvent::Disconnected(id))
+    }
+
+    /// Set the state of a gamepad for this frame.
+    ///
+    /// The current state become the previous state.
+    /// # Arguments
+    /// * `id` - The joystick slot of the gamepad.
+    /// * `buttons` - The buttons state, indexed by `GamepadButton`.
+    /// * `axes` - The raw axes values, indexed by `GamepadAxis`.
+    pub fn set_state(&mut self, id: JoystickId, buttons: [bool; BUTTON_COUNT], axes: [f32; AXIS_COUNT]) {
+        if let Some(pad) = self.pads.iter_mut().find(|p| p.id == id) {
+            pad.prev_buttons = pad.buttons;
+            pad.prev_axes = pad.axes;
+            pad.buttons = buttons;
+            pad.axes = axes;
+        }
+    }
+
+    /// Get a connected gamepad.
+    /// # Arguments
+    /// * `id` - The joystick slot of the gamepad.
+    pub fn get(&self, id: JoystickId) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    /// All the connected gamepads.
+    pub fn iter(&self) -> std::slice::Iter<'_, Gamepad> {
+        self.pads.iter()
+    }
+
+    /// Apply the deadzone to an axis value.
+    ///
+    /// Triggers rest at `-1.0` so only the sticks have a deadzone.
+    /// # Arguments
+    /// * `axis` - The axis.
+    /// * `value` - The raw value of the axis.
+    pub fn apply_deadzone(&self, axis: GamepadAxis, value: f32) -> f32 {
+        match axis {
+            GamepadAxis::AxisLeftTrigger | GamepadAxis::AxisRightTrigger => value,
+            _ if value.abs() <= self.deadzone => 0.0,
+            _ => value.signum() * (value.abs() - self.deadzone) / (1.0 - self.deadzone),
+        }
+    }
+
+    /// The value of an axis of a gamepad, with the deadzone applied.
+    /// # Arguments
+    /// * `id` - The joystick slot of the gamepad.
+    /// * `axis` - The axis.
+    pub fn axis(&self, id: JoystickId, axis: GamepadAxis) -> f32 {
+        match self.get(id) {
+            Some(pad) => self.apply_deadzone(axis, pad.raw_axis(axis)),
+            None => 0.0,
+        }
+    }
+
+    /// `true` while the button is held down on any gamepad.
+    pub fn is_button_down(&self, button: GamepadButton) -> bool {
+        self.pads.iter().any(|p| p.is_button_down(button))
+    }
+
+    /// `true` if the button was pressed on any gamepad during this frame.
+    pub fn is_button_just_pressed(&self, button: GamepadButton) -> bool {
+        self.pads.iter().any(|p| p.is_button_just_pressed(button))
+    }
+
+    /// `true` if the button was released on any gamepad during this frame.
+    pub fn is_button_just_released(&self, button: GamepadButton) -> bool {
+        self.pads.iter().any(|p| p.is_button_just_released(button))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_rescales_the_sticks() {
+        let gamepads = Gamepads::new(0.2);
+
+        assert_eq!(gamepads.apply_deadzone(GamepadAxis::AxisLeftX, 0.1), 0.0);
+        assert_eq!(gamepads.apply_deadzone(GamepadAxis::AxisLeftY, -0.2), 0.0);
+        assert!((gamepads.apply_deadzone(GamepadAxis::AxisRightX, 0.6) - 0.5).abs() < 1e-6);
+        assert!((gamepads.apply_deadzone(GamepadAxis::AxisRightY, -1.0) + 1.0).abs() < 1e-6);
+
+        // Triggers rest at -1.0, they have no deadzone.
+        assert_eq!(gamepads.apply_deadzone(GamepadAxis::AxisLeftTrigger, 0.1), 0.1);
+        assert_eq!(gamepads.apply_deadzone(GamepadAxis::AxisRightTrigger, -1.0), -1.0);
+    }
+
+    #[test]
+    fn set_state_tracks_edges() {
+        let mut gamepads = Gamepads::new(0.2);
+        let (a, b) = (GamepadButton::ButtonA as usize, GamepadButton::ButtonB as usize);
+
+        assert_eq!(gamepads.connect(JoystickId::Joystick2, "pad"), Some(GamepadEvent::Connected(JoystickId::Joystick2)));
+        assert_eq!(gamepads.connect(JoystickId::Joystick2, "pad"), None);
+
+        let mut buttons = [false; BUTTON_COUNT];
+        let mut axes = [0.0; AXIS_COUNT];
+        buttons[a] = true;
+        axes[GamepadAxis::AxisLeftY as usize] = 0.6;
+        gamepads.set_state(JoystickId::Joystick2, buttons, axes);
+
+        assert!(gamepads.is_button_just_pressed(GamepadButton::ButtonA));
+        assert!(!gamepads.is_button_down(GamepadButton::ButtonB));
+        assert!((gamepads.axis(JoystickId::Joystick2, GamepadAxis::AxisLeftY) - 0.5).abs() < 1e-6);
+
+        buttons[a] = false;
+        buttons[b] = true;
+        gamepads.set_state(JoystickId::Joystick2, buttons, axes);
+
+        assert!(gamepads.is_button_just_released(GamepadButton::ButtonA));
+        assert!(gamepads.is_button_just_pressed(GamepadButton::ButtonB));
+        assert_eq!(gamepads.get(JoystickId::Joystick2).unwrap().prev_raw_axis(GamepadAxis::AxisLeftY), 0.6);
+
+        // Unknown gamepads are ignored.
+        gamepads.set_state(JoystickId::Joystick3, [true; BUTTON_COUNT], axes);
+        assert!(!gamepads.is_button_down(GamepadButton::ButtonX));
+        assert_eq!(gamepads.axis(JoystickId::Joystick3, GamepadAxis::AxisLeftY), 0.0);
+
+        assert_eq!(gamepads.disconnect(JoystickId::Joystick2), Some(GamepadEvent::Disconnected(JoystickId::Joystick2)));
+        assert!(!gamepads.is_button_down(GamepadButton::ButtonB));
+    }
+}
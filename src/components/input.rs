@@ -0,0 +1,248 @@
+
+use std::collections::HashSet;
+
+use cgmath::Vector2;
+use glfw::{Action, Key, MouseButton};
+
+use crate::core::{
+    entity::Entity,
+    platform_event::PlatformEvent,
+};
+
+/// The keyboard and mouse state, updated once
+/// per frame from the platform events by `InputSys`.
+pub struct Input {
+    /// The keys currently held down.
+    keys_down: HashSet<Key>,
+
+    /// The keys pressed during this frame.
+    keys_pressed: HashSet<Key>,
+
+    /// The keys released during this frame.
+    keys_released: HashSet<Key>,
+
+    /// The mouse buttons currently held down.
+    buttons_down: HashSet<MouseButton>,
+
+    /// The mouse buttons pressed during this frame.
+    buttons_pressed: HashSet<MouseButton>,
+
+    /// The mouse buttons released during this frame.
+    buttons_released: HashSet<MouseButton>,
+
+    /// The cursor position in screen coordinates, from the top left corner of the window.
+    pub cursor: Vector2<f32>,
+
+    /// The window entity the cursor last moved over, `None` until
+    /// the cursor moves. `cursor` is relative to this window.
+    pub cursor_window: Option<Entity>,
+
+    /// The cursor position in world space, seen from the first camera
+    /// of `cursor_window`. `None` when the window has no camera.
+    pub cursor_world: Option<Vector2<f32>>,
+
+    /// The scroll amount during this frame.
+    pub scroll: Vector2<f32>,
+
+    /// The characters typed during this frame.
+    pub text: String,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::new()
+    }
+}
+
+impl Input {
+    /// Create new `Input` with nothing pressed.
+    pub fn new() -> Self {
+        Self {
+            keys_down: HashSet::new(),
+            keys_pressed: HashSet::new(),
+            keys_released: HashSet::new(),
+            buttons_down: HashSet::new(),
+            buttons_pressed: HashSet::new(),
+            buttons_released: HashSet::new(),
+            cursor: Vector2::new(0.0, 0.0),
+            cursor_window: None,
+            cursor_world: None,
+            scroll: Vector2::new(0.0, 0.0),
+            text: String::new(),
+        }
+    }
+
+    /// Forget the per frame state (just pressed/released, scroll and text).
+    pub fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.scroll = Vector2::new(0.0, 0.0);
+        self.text.clear();
+    }
+
+    /// Update the state from a platform event.
+    /// # Arguments
+    /// * `event` - The platform event.
+    pub fn apply(&mut self, event: &PlatformEvent) {
+        match *event {
+            PlatformEvent::Key { key, action: Action::Press, .. } => {
+                self.keys_down.insert(key);
+                self.keys_pressed.insert(key);
+            },
+            PlatformEvent::Key { key, action: Action::Release, .. } => {
+                self.keys_down.remove(&key);
+                self.keys_released.insert(key);
+            },
+            PlatformEvent::MouseButton { button, action: Action::Press, .. } => {
+                self.buttons_down.insert(button);
+                self.buttons_pressed.insert(button);
+            },
+            PlatformEvent::MouseButton { button, action: Action::Release, .. } => {
+                self.buttons_down.remove(&button);
+                self.buttons_released.insert(button);
+            },
+            PlatformEvent::CursorMoved { x, y } => {
+                self.cursor = Vector2::new(x as f32, y as f32);
+            },
+            PlatformEvent::Scroll { x, y } => {
+                self.scroll += Vector2::new(x as f32, y as f32);
+            },
+            PlatformEvent::Char(c) => {
+                self.text.push(c);
+            },
+            // Release everything when the window lose the focus,
+            // otherwise the release events would never be received.
+            PlatformEvent::Focused(false) => {
+                self.keys_released.extend(self.keys_down.drain());
+                self.buttons_released.extend(self.buttons_down.drain());
+            },
+            _ => {},
+        }
+    }
+
+    /// `true` while the key is held down.
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// `true` if the key was pressed during this frame.
+    pub fn is_key_just_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// `true` if the key was released during this frame.
+    pub fn is_key_just_released(&self, key: Key) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// `true` while the mouse button is held down.
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    /// `true` if the mouse button was pressed during this frame.
+    pub fn is_button_just_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// `true` if the mouse button was released during this frame.
+    pub fn is_button_just_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glfw::Modifiers;
+
+    fn key(key: Key, action: Action) -> PlatformEvent {
+        PlatformEvent::Key { key, scancode: 0, action, mods: Modifiers::empty() }
+    }
+
+    fn button(button: MouseButton, action: Action) -> PlatformEvent {
+        PlatformEvent::MouseButton { button, action, mods: Modifiers::empty() }
+    }
+
+    #[test]
+    fn key_edges_last_one_frame() {
+        let mut input = Input::new();
+
+        input.begin_frame();
+        input.apply(&key(Key::A, Action::Press));
+        assert!(input.is_key_down(Key::A));
+        assert!(input.is_key_just_pressed(Key::A));
+        assert!(!input.is_key_just_released(Key::A));
+
+        // Held: still down, no longer just pressed. Repeats don't press again.
+        input.begin_frame();
+        input.apply(&key(Key::A, Action::Repeat));
+        assert!(input.is_key_down(Key::A));
+        assert!(!input.is_key_just_pressed(Key::A));
+
+        input.begin_frame();
+        input.apply(&key(Key::A, Action::Release));
+        assert!(!input.is_key_down(Key::A));
+        assert!(input.is_key_just_released(Key::A));
+
+        input.begin_frame();
+        assert!(!input.is_key_just_released(Key::A));
+    }
+
+    #[test]
+    fn press_and_release_in_the_same_frame() {
+        let mut input = Input::new();
+
+        input.begin_frame();
+        input.apply(&button(MouseButton::Button1, Action::Press));
+        input.apply(&button(MouseButton::Button1, Action::Release));
+
+        // The click is not lost even if the button is already up.
+        assert!(!input.is_button_down(MouseButton::Button1));
+        assert!(input.is_button_just_pressed(MouseButton::Button1));
+        assert!(input.is_button_just_released(MouseButton::Button1));
+
+        input.begin_frame();
+        assert!(!input.is_button_just_pressed(MouseButton::Button1));
+        assert!(!input.is_button_just_released(MouseButton::Button1));
+    }
+
+    #[test]
+    fn focus_loss_releases_everything() {
+        let mut input = Input::new();
+
+        input.apply(&key(Key::W, Action::Press));
+        input.apply(&button(MouseButton::Button2, Action::Press));
+
+        input.begin_frame();
+        input.apply(&PlatformEvent::Focused(false));
+
+        assert!(!input.is_key_down(Key::W));
+        assert!(input.is_key_just_released(Key::W));
+        assert!(!input.is_button_down(MouseButton::Button2));
+        assert!(input.is_button_just_released(MouseButton::Button2));
+    }
+
+    #[test]
+    fn scroll_and_text_are_per_frame() {
+        let mut input = Input::new();
+
+        input.apply(&PlatformEvent::Scroll { x: 0.0, y: 1.0 });
+        input.apply(&PlatformEvent::Scroll { x: 0.5, y: 2.0 });
+        input.apply(&PlatformEvent::Char('h'));
+        input.apply(&PlatformEvent::Char('i'));
+        input.apply(&PlatformEvent::CursorMoved { x: 10.0, y: 20.0 });
+
+        assert_eq!(input.scroll, Vector2::new(0.5, 3.0));
+        assert_eq!(input.text, "hi");
+
+        input.begin_frame();
+        assert_eq!(input.scroll, Vector2::new(0.0, 0.0));
+        assert!(input.text.is_empty());
+
+        // The cursor position is kept until it moves again.
+        assert_eq!(input.cursor, Vector2::new(10.0, 20.0));
+    }
+}
@@ -0,0 +1,26 @@
+
+use std::{fs::File, io::BufWriter};
+
+use crate::core::input_record::RecordedFrame;
+
+/// Where `InputRecordSys` write the recorded frames.
+pub struct InputRecorder {
+    /// The recording file.
+    pub file: BufWriter<File>,
+}
+
+/// The recorded frames played by `InputReplaySys`.
+pub struct InputReplay {
+    /// The recorded frames.
+    pub frames: Vec<RecordedFrame>,
+
+    /// The index of the next frame to play.
+    pub next: usize,
+}
+
+impl InputReplay {
+    /// `true` once all frames were played.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.frames.len()
+    }
+}
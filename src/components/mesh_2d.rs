@@ -1,8 +1,17 @@
 
-use crate::core::gl::shader::Shader;
+use std::{any::Any, ops::Range, rc::Rc};
+
+use crate::core::shapes;
+use crate::core::gl::{
+    material::Material,
+    object::{self, GlObject, GlObjectKind},
+    vertex::{self, AttributeType, Vertex, VertexLayout},
+};
 use gl::types::{
-    GLsizei,
+    GLenum,
+    GLintptr,
     GLsizeiptr,
+    GLvoid,
 };
 
 use cgmath::{
@@ -11,6 +20,11 @@ use cgmath::{
 };
 
 /// A two dimensional vertex representation.
+///
+/// Its attributes are the position (location 0),
+/// the normal (location 1) and the uv coordinates (location 2).
+#[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Vertex2D {
     /// The vertex position.
     pub pos:    Vector2<f32>,
@@ -37,77 +51,899 @@ impl Vertex2D {
     }
 }
 
+crate::impl_vertex!(Vertex2D { pos => 0, norm => 1, uv => 2 });
+
+/// A mesh error.
+#[derive(Debug)]
+pub enum MeshError {
+    /// The vertex layout is invalid, doesn't feed the shader or isn't the mesh vertex type.
+    Layout(String),
+
+    /// Indices or vertices are out of range.
+    Range(String),
+
+    /// The elements don't make whole primitives.
+    Topology(String),
+
+    /// The mesh has no vertices or an empty index buffer.
+    Empty(String),
+
+    /// A triangle has no area.
+    Degenerate(String),
+
+    /// A polygon can't be triangulated.
+    Polygon(String),
+}
+
+impl std::fmt::Display for MeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshError::Layout(msg)
+            | MeshError::Range(msg)
+            | MeshError::Topology(msg)
+            | MeshError::Empty(msg)
+            | MeshError::Degenerate(msg)
+            | MeshError::Polygon(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+/// The vertices of a mesh, of any vertex type.
+trait VertexData {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// The amount of vertices.
+    fn len(&self) -> usize;
+
+    /// The vertices, as uploaded to the vertex buffer.
+    fn as_ptr(&self) -> *const GLvoid;
+}
+
+impl<V: Vertex> VertexData for Vec<V> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn as_ptr(&self) -> *const GLvoid {
+        Vec::as_ptr(self) as *const GLvoid
+    }
+}
+
+/// How the vertices (or the indices) are assembled into primitives.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Topology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Topology {
+    /// The GL enum of the topology.
+    pub fn gl_enum(self) -> GLenum {
+        match self {
+            Topology::Points        => gl::POINTS,
+            Topology::Lines         => gl::LINES,
+            Topology::LineStrip     => gl::LINE_STRIP,
+            Topology::LineLoop      => gl::LINE_LOOP,
+            Topology::Triangles     => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan   => gl::TRIANGLE_FAN,
+        }
+    }
+
+    /// The amount of elements of each primitive, `1` for strips, fans and loops.
+    pub fn primitive_size(self) -> usize {
+        match self {
+            Topology::Lines => 2,
+            Topology::Triangles => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// The indices of a mesh, in the smallest format that fits.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Indices {
+    /// No index buffer, the vertices are drawn in order.
+    None,
+
+    /// Up to 65536 vertices.
+    U16(Vec<u16>),
+
+    /// Up to 4294967296 vertices.
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// Store indices as `u16` when they all fit, as `u32` otherwise.
+    /// # Arguments
+    /// * `indices` - The indices.
+    pub fn compact(indices: Vec<u32>) -> Self {
+        if indices.iter().all(|i| *i <= u16::MAX as u32) {
+            Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    /// The amount of indices, `0` for `Indices::None`.
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::None => 0,
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    /// `true` if there are no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an index.
+    /// # Arguments
+    /// * `i` - The position of the index.
+    pub fn get(&self, i: usize) -> Option<u32> {
+        match self {
+            Indices::None => None,
+            Indices::U16(indices) => indices.get(i).map(|i| *i as u32),
+            Indices::U32(indices) => indices.get(i).copied(),
+        }
+    }
+
+    /// The greatest index, `None` if there are no indices.
+    pub fn max(&self) -> Option<u32> {
+        match self {
+            Indices::None => None,
+            Indices::U16(indices) => indices.iter().max().map(|i| *i as u32),
+            Indices::U32(indices) => indices.iter().max().copied(),
+        }
+    }
+
+    /// The GL type and the size in bytes of an index, `None` for `Indices::None`.
+    pub fn gl_type(&self) -> Option<(GLenum, usize)> {
+        match self {
+            Indices::None => None,
+            Indices::U16(_) => Some((gl::UNSIGNED_SHORT, 2)),
+            Indices::U32(_) => Some((gl::UNSIGNED_INT, 4)),
+        }
+    }
+
+    fn as_ptr(&self) -> *const GLvoid {
+        match self {
+            Indices::None => std::ptr::null(),
+            Indices::U16(indices) => indices.as_ptr() as *const GLvoid,
+            Indices::U32(indices) => indices.as_ptr() as *const GLvoid,
+        }
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+impl From<Vec<Vector3<u16>>> for Indices {
+    /// Flatten triangles.
+    fn from(triangles: Vec<Vector3<u16>>) -> Self {
+        Indices::U16(triangles.iter().flat_map(|t| [t.x, t.y, t.z]).collect())
+    }
+}
+
+impl From<Vec<Vector3<u32>>> for Indices {
+    /// Flatten triangles.
+    fn from(triangles: Vec<Vector3<u32>>) -> Self {
+        Indices::U32(triangles.iter().flat_map(|t| [t.x, t.y, t.z]).collect())
+    }
+}
+
+/// Check that indices refer to existing vertices and make whole primitives.
+///
+/// # Arguments
+/// * `indices` - The indices.
+/// * `vertex_count` - The amount of vertices.
+/// * `topology` - How the indices (or the vertices) are assembled.
+fn check_indices(indices: &Indices, vertex_count: usize, topology: Topology) -> Result<(), MeshError> {
+    if let Some(max) = indices.max() {
+        if max as usize >= vertex_count {
+            let msg = format!("Index {} is out of the {} vertices of the mesh", max, vertex_count);
+            return Err(MeshError::Range(msg))
+        }
+    }
+
+    let count = match indices {
+        Indices::None => vertex_count,
+        _ => indices.len(),
+    };
+
+    if count % topology.primitive_size() != 0 {
+        let msg = format!("{} elements don't make whole {:?} primitives", count, topology);
+        return Err(MeshError::Topology(msg))
+    }
+
+    Ok(())
+}
+
+/// Upload the modified part of a buffer, the buffer must be bound.
+///
+/// The buffer grows when the data doesn't fit anymore, and is
+/// orphaned when all the data is replaced so the GPU doesn't have
+/// to wait for the draw calls still using the previous content.
+///
+/// # Arguments
+/// * `target` - The buffer target (`gl::ARRAY_BUFFER`, ...).
+/// * `capacity` - The amount of elements the buffer holds, updated when it grows.
+/// * `dirty` - The modified elements.
+/// * `count` - The amount of elements.
+/// * `size` - The size of an element in bytes.
+/// * `data` - The elements.
+/// * `usage` - The buffer usage (`gl::STATIC_DRAW`, ...).
+unsafe fn upload_buffer(target: GLenum, capacity: &mut usize, dirty: Range<usize>, count: usize, size: usize, data: *const GLvoid, usage: GLenum) {
+    if count > *capacity || (dirty.start == 0 && dirty.end >= count) {
+        if count > *capacity {
+            // Dynamic buffers keep some room to grow without reallocating each time.
+            *capacity = if usage == gl::DYNAMIC_DRAW { count.next_power_of_two() } else { count };
+        }
+
+        gl::BufferData(target, (*capacity * size) as GLsizeiptr, std::ptr::null(), usage);
+        gl::BufferSubData(target, 0, (count * size) as GLsizeiptr, data);
+    } else if dirty.start < dirty.end {
+        let start = dirty.start * size;
+        let len = (dirty.end.min(count) - dirty.start) * size;
+        gl::BufferSubData(target, start as GLintptr, len as GLsizeiptr, (data as *const u8).add(start) as *const GLvoid);
+    }
+}
+
+/// Merge a modified range into the modified range of a buffer.
+fn merge_dirty(dirty: &mut Option<Range<usize>>, range: Range<usize>) {
+    *dirty = Some(match dirty.take() {
+        Some(d) => d.start.min(range.start)..d.end.max(range.end),
+        None => range,
+    });
+}
+
+/// A mesh, drawn by `RenderSys` with its material.
+///
+/// The vertices and indices can be replaced or modified after
+/// creation, the mesh is then marked dirty and `RenderSys`
+/// uploads it before drawing.
+///
+/// The buffers are shared by all windows, but each window
+/// context gets its own vertex array (see `prepare_context`).
 pub struct Mesh2D {
-    pub vertices: Vec<Vertex2D>,
-    pub indices: Vec<Vector3<u16>>,
-    pub vbo: u32,
-    pub vao: u32,
-    pub ebo: u32,
-    pub shader: Shader,
+    /// The vertices, of the vertex type the mesh was created with.
+    vertices: Box<dyn VertexData>,
+
+    /// How the vertices are laid out.
+    layout: VertexLayout,
+
+    /// The indices of the vertices to draw.
+    indices: Indices,
+
+    /// How the vertices are assembled into primitives.
+    topology: Topology,
+
+    /// The drawn elements (indices, or vertices without indices), all of them if `None`.
+    draw_range: Option<Range<usize>>,
+
+    pub vbo: GlObject,
+    pub ebo: GlObject,
+
+    /// The vertex arrays, one per context the mesh is drawn into.
+    vaos: Vec<GlObject>,
+
+    /// How the mesh looks, shared with the other meshes that look the same.
+    pub material: Rc<Material>,
+
+    /// `true` if the mesh is updated often, its buffers are `gl::DYNAMIC_DRAW`.
+    is_dynamic: bool,
+
+    /// The amount of vertices the vertex buffer holds.
+    vertex_capacity: usize,
+
+    /// The amount of indices the index buffer holds.
+    index_capacity: usize,
+
+    /// The vertices modified since the last upload.
+    dirty_vertices: Option<Range<usize>>,
+
+    /// The indices modified since the last upload.
+    dirty_indices: Option<Range<usize>>,
 }
 
 impl Mesh2D {
-    pub fn new(vertices: Vec<Vertex2D>, indices: Vec<Vector3<u16>>, shader: Shader, is_dynamic: bool) -> Self {
-        let mut mesh2d = Self {
-            vertices,
+    /// Create new `Mesh2D` of triangles and upload it to the GPU.
+    ///
+    /// Fails if the vertex layout is invalid or doesn't provide
+    /// the vertex attributes the material shader reads, or if
+    /// the indices don't refer to the vertices.
+    /// # Arguments
+    /// * `vertices` - The vertices, of any vertex type.
+    /// * `indices` - The indices (`Vec<u16>`, `Vec<u32>`, triangles or `Indices::None`).
+    /// * `material` - How the mesh looks.
+    /// * `is_dynamic` - `true` if the mesh will be updated often.
+    pub fn new<V: Vertex>(vertices: Vec<V>, indices: impl Into<Indices>, material: Rc<Material>, is_dynamic: bool) -> Result<Self, MeshError> {
+        Mesh2D::with_topology(vertices, indices, Topology::Triangles, material, is_dynamic)
+    }
+
+    /// Create new `Mesh2D` of any primitive and upload it to the GPU.
+    ///
+    /// Fails if there are no vertices, or if the index buffer is empty
+    /// (use `Indices::None` to draw the vertices in order). Meshes filled
+    /// later are created with `Mesh2D::empty`.
+    /// # Arguments
+    /// * `vertices` - The vertices, of any vertex type.
+    /// * `indices` - The indices (`Vec<u16>`, `Vec<u32>`, triangles or `Indices::None`).
+    /// * `topology` - How the vertices are assembled into primitives.
+    /// * `material` - How the mesh looks.
+    /// * `is_dynamic` - `true` if the mesh will be updated often.
+    pub fn with_topology<V: Vertex>(vertices: Vec<V>, indices: impl Into<Indices>, topology: Topology, material: Rc<Material>, is_dynamic: bool) -> Result<Self, MeshError> {
+        let indices = indices.into();
+
+        if vertices.is_empty() {
+            return Err(MeshError::Empty(String::from("A mesh needs vertices, use Mesh2D::empty for a mesh filled later")))
+        }
+
+        if indices != Indices::None && indices.is_empty() {
+            return Err(MeshError::Empty(String::from("The index buffer is empty, use Indices::None to draw the vertices in order")))
+        }
+
+        check_indices(&indices, vertices.len(), topology)?;
+
+        let mut mesh2d = Mesh2D::create(vertices, indices, topology, material, is_dynamic)?;
+        mesh2d.upload();
+        Ok(mesh2d)
+    }
+
+    /// Create new `Mesh2D` without vertices, filled later with `set_geometry`.
+    ///
+    /// The GPU buffers are created by the first upload with vertices.
+    /// # Arguments
+    /// * `topology` - How the vertices will be assembled into primitives.
+    /// * `material` - How the mesh looks.
+    /// * `is_dynamic` - `true` if the mesh will be updated often.
+    pub fn empty<V: Vertex>(topology: Topology, material: Rc<Material>, is_dynamic: bool) -> Result<Self, MeshError> {
+        Mesh2D::create(Vec::<V>::new(), Indices::None, topology, material, is_dynamic)
+    }
+
+    /// Create the mesh, without GPU buffers.
+    fn create<V: Vertex>(vertices: Vec<V>, indices: Indices, topology: Topology, material: Rc<Material>, is_dynamic: bool) -> Result<Self, MeshError> {
+        let layout = V::layout();
+
+        layout.validate()
+            .and_then(|_| vertex::check_compatibility(&layout, material.get_shader().get_attributes().values()))
+            .map_err(MeshError::Layout)?;
+
+        Ok(Self {
+            dirty_vertices: Some(0..vertices.len()),
+            dirty_indices: Some(0..indices.len()),
+            vertices: Box::new(vertices),
+            layout,
             indices,
-            shader,
-            vbo: 0,
-            vao: 0,
-            ebo: 0,
+            topology,
+            draw_range: None,
+            material,
+            vbo: GlObject::null(GlObjectKind::Buffer),
+            ebo: GlObject::null(GlObjectKind::Buffer),
+            vaos: Vec::new(),
+            is_dynamic,
+            vertex_capacity: 0,
+            index_capacity: 0,
+        })
+    }
+
+    /// Fail if the mesh has degenerate triangles, with two identical
+    /// indices or (when the layout has a float position at location 0) no area.
+    ///
+    /// ```ignore
+    /// let mesh = Mesh2D::new(vertices, indices, material, false)?.reject_degenerate()?;
+    /// ```
+    pub fn reject_degenerate(self) -> Result<Self, MeshError> {
+        match self.degenerate_triangles().first() {
+            Some(triangle) => Err(MeshError::Degenerate(format!("Triangle {} of the mesh is degenerate", triangle))),
+            None => Ok(self),
+        }
+    }
+
+    /// The degenerate triangles, with two identical indices or (when the
+    /// layout has a float position at location 0) no area, relative to
+    /// the size of the mesh.
+    ///
+    /// Empty for the meshes that are not `Topology::Triangles`.
+    pub fn degenerate_triangles(&self) -> Vec<usize> {
+        if self.topology != Topology::Triangles {
+            return Vec::new()
+        }
+
+        let position = self.layout.get_attribute(0)
+            .filter(|a| a.attr_type == AttributeType::Float && a.components >= 2)
+            .map(|a| a.offset);
+
+        let stride = self.layout.get_stride();
+        let data = self.vertices.as_ptr() as *const u8;
+
+        let positions = match position {
+            Some(offset) => (0..self.vertices.len()).map(|vertex| {
+                // SAFETY: the layout was validated, the position is in the vertex.
+                unsafe {
+                    let p = data.add(vertex * stride + offset) as *const f32;
+                    Vector2::new(p.read_unaligned(), p.add(1).read_unaligned())
+                }
+            }).collect(),
+            None => Vec::new(),
         };
 
-        unsafe { mesh2d.init_buffers(is_dynamic); }
-        mesh2d
+        // Areas are compared to a tolerance scaled like them, by the size of the mesh squared.
+        let epsilon = shapes::extent(&positions).powi(2) * shapes::TOLERANCE;
+
+        (0..self.element_count() / 3).filter(|t| {
+            let index = |i: usize| match self.indices {
+                Indices::None => (t * 3 + i) as u32,
+                _ => self.indices.get(t * 3 + i).unwrap(),
+            };
+
+            let (a, b, c) = (index(0), index(1), index(2));
+
+            if a == b || b == c || a == c {
+                return true
+            }
+
+            if positions.is_empty() {
+                return false
+            }
+
+            let (pa, pb, pc) = (positions[a as usize], positions[b as usize], positions[c as usize]);
+            let area = (pb.x - pa.x) * (pc.y - pa.y) - (pc.x - pa.x) * (pb.y - pa.y);
+            area.abs() <= epsilon
+        }).collect()
     }
 
-    unsafe fn init_buffers(&mut self, is_dynamic: bool) {
-        
-        gl::GenVertexArrays(1, &mut self.vao);
-        gl::GenBuffers(1, &mut self.vbo);
-        gl::GenBuffers(1, &mut self.ebo);
+    /// The vertices, `None` if the mesh was created with another vertex type.
+    pub fn get_vertices<V: Vertex>(&self) -> Option<&[V]> {
+        self.vertices.as_any().downcast_ref::<Vec<V>>().map(|v| v.as_slice())
+    }
 
-        gl::BindVertexArray(self.vao);
-        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+    /// The amount of vertices.
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
 
-        let i_buff_size = self.i_buff_size();
-        let buff_size = self.vertices.len() * std::mem::size_of::<Vertex2D>();
-        let draw_mode = if is_dynamic { gl::DYNAMIC_DRAW } else { gl::STATIC_DRAW };
+    /// How the vertices are laid out.
+    pub fn get_layout(&self) -> &VertexLayout {
+        &self.layout
+    }
 
-        //let v_size = std::mem::size_of::<Vector2<f32>>();
-        //let n_size = std::mem::size_of::<Vector2<f32>>();
-        //let u_size = std::mem::size_of::<Vector2<f32>>();
-        let stride = std::mem::size_of::<Vertex2D>() as GLsizei; //(v_size + n_size + u_size) as GLsizei;
+    /// The indices of the vertices to draw.
+    pub fn get_indices(&self) -> &Indices {
+        &self.indices
+    }
 
-        gl::BufferData(gl::ARRAY_BUFFER, buff_size as isize, std::ptr::null(), draw_mode);
-        gl::BufferSubData(gl::ARRAY_BUFFER, 0, buff_size as isize, &self.vertices[0] as *const Vertex2D as *const gl::types::GLvoid);
+    /// How the vertices are assembled into primitives.
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
 
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
-        gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, i_buff_size, &self.indices[0] as *const Vector3::<u16> as *const gl::types::GLvoid, draw_mode);
+    /// Change how the vertices are assembled into primitives.
+    /// # Arguments
+    /// * `topology` - The new topology, the elements must make whole primitives.
+    pub fn set_topology(&mut self, topology: Topology) -> Result<(), MeshError> {
+        check_indices(&self.indices, self.vertices.len(), topology)?;
+        self.topology = topology;
+        Ok(())
+    }
 
-        gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
-        gl::EnableVertexAttribArray(0);
+    /// The drawn elements, all of them if `None`.
+    pub fn get_draw_range(&self) -> Option<Range<usize>> {
+        self.draw_range.clone()
+    }
 
-        gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::TRUE, stride, std::mem::size_of::<Vector2<f32>>() as *const gl::types::GLvoid);
-        gl::EnableVertexAttribArray(1);
+    /// Draw only some elements (indices, or vertices without indices).
+    /// # Arguments
+    /// * `range` - The drawn elements, all of them if `None`. Clamped to the elements when drawing.
+    pub fn set_draw_range(&mut self, range: Option<Range<usize>>) {
+        self.draw_range = range;
+    }
 
-        gl::VertexAttribPointer(2, 2, gl::FLOAT, gl::TRUE, stride, (std::mem::size_of::<Vector2<f32>>() * 2) as *const gl::types::GLvoid);
-        gl::EnableVertexAttribArray(2);
+    /// The amount of elements (indices, or vertices without indices).
+    pub fn element_count(&self) -> usize {
+        match self.indices {
+            Indices::None => self.vertices.len(),
+            _ => self.indices.len(),
+        }
+    }
 
-        gl::BindBuffer(gl::ARRAY_BUFFER, 0);
-        gl::BindVertexArray(0);
+    /// `true` if the mesh is updated often.
+    pub fn is_dynamic(&self) -> bool {
+        self.is_dynamic
     }
 
-    /// The index buffer size.
-    fn i_buff_size(&self) -> GLsizeiptr {
-        (self.indices.len() * std::mem::size_of::<Vector3::<u16>>()) as GLsizeiptr
+    /// `true` if the mesh was modified since the last upload.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_vertices.is_some() || self.dirty_indices.is_some()
     }
 
+    /// The vertices, mutably, `None` if the mesh was created with another vertex type.
+    fn vertices_mut<V: Vertex>(&mut self) -> Result<&mut Vec<V>, MeshError> {
+        self.vertices.as_any_mut().downcast_mut::<Vec<V>>().ok_or_else(|| {
+            let msg = format!("The mesh vertices are not '{}'", std::any::type_name::<V>());
+            MeshError::Layout(msg)
+        })
+    }
+
+    /// Replace all the vertices, uploaded by `RenderSys` before drawing.
+    ///
+    /// The vertices must be of the vertex type the mesh was created
+    /// with, and the indices must still refer to them.
+    /// # Arguments
+    /// * `vertices` - The new vertices.
+    pub fn set_vertices<V: Vertex>(&mut self, vertices: Vec<V>) -> Result<(), MeshError> {
+        self.vertices_mut::<V>()?;
+        check_indices(&self.indices, vertices.len(), self.topology)?;
+
+        let len = vertices.len();
+        *self.vertices_mut::<V>()? = vertices;
+
+        self.dirty_vertices = Some(0..len);
+        Ok(())
+    }
+
+    /// Replace the vertices and the indices together, uploaded by `RenderSys` before drawing.
+    ///
+    /// The indices are checked against the new vertices, so a mesh can change
+    /// both at once (like a mesh created with `Mesh2D::empty`).
+    /// # Arguments
+    /// * `vertices` - The new vertices, of the vertex type the mesh was created with.
+    /// * `indices` - The new indices (`Vec<u16>`, `Vec<u32>`, triangles or `Indices::None`).
+    pub fn set_geometry<V: Vertex>(&mut self, vertices: Vec<V>, indices: impl Into<Indices>) -> Result<(), MeshError> {
+        let indices = indices.into();
+        self.vertices_mut::<V>()?;
+        check_indices(&indices, vertices.len(), self.topology)?;
+
+        // The index buffer capacity is counted in indices of the previous format.
+        if indices.gl_type() != self.indices.gl_type() {
+            self.index_capacity = 0;
+        }
+
+        let len = vertices.len();
+        *self.vertices_mut::<V>()? = vertices;
+
+        self.dirty_vertices = Some(0..len);
+        self.dirty_indices = Some(0..indices.len());
+        self.indices = indices;
+        Ok(())
+    }
+
+    /// Replace a range of vertices, uploaded by `RenderSys` before drawing.
+    /// # Arguments
+    /// * `start` - The index of the first replaced vertex.
+    /// * `vertices` - The new vertices, they must fit in the current vertices.
+    pub fn update_vertices<V: Vertex>(&mut self, start: usize, vertices: &[V]) -> Result<(), MeshError> {
+        let current = self.vertices_mut::<V>()?;
+        let end = start + vertices.len();
+
+        if end > current.len() {
+            let msg = format!("Vertices {}..{} are out of the {} vertices of the mesh", start, end, current.len());
+            return Err(MeshError::Range(msg))
+        }
+
+        current[start..end].copy_from_slice(vertices);
+        merge_dirty(&mut self.dirty_vertices, start..end);
+        Ok(())
+    }
+
+    /// Replace all the indices, uploaded by `RenderSys` before drawing.
+    /// # Arguments
+    /// * `indices` - The new indices (`Vec<u16>`, `Vec<u32>`, triangles or `Indices::None`).
+    pub fn set_indices(&mut self, indices: impl Into<Indices>) -> Result<(), MeshError> {
+        let indices = indices.into();
+        check_indices(&indices, self.vertices.len(), self.topology)?;
+
+        // The index buffer capacity is counted in indices of the previous format.
+        if indices.gl_type() != self.indices.gl_type() {
+            self.index_capacity = 0;
+        }
+
+        self.dirty_indices = Some(0..indices.len());
+        self.indices = indices;
+        Ok(())
+    }
+
+    /// Replace a range of indices, uploaded by `RenderSys` before drawing.
+    /// # Arguments
+    /// * `start` - The position of the first replaced index.
+    /// * `indices` - The new indices, they must fit in the current indices. `u16` indices
+    ///   are stored as `u32` from the first index that doesn't fit in `u16`.
+    pub fn update_indices(&mut self, start: usize, indices: &[u32]) -> Result<(), MeshError> {
+        let end = start + indices.len();
+
+        if end > self.indices.len() {
+            let msg = format!("Indices {}..{} are out of the {} indices of the mesh", start, end, self.indices.len());
+            return Err(MeshError::Range(msg))
+        }
+
+        let vertex_count = self.vertices.len();
+
+        if let Some(index) = indices.iter().find(|i| **i as usize >= vertex_count) {
+            let msg = format!("Index {} is out of the {} vertices of the mesh", index, vertex_count);
+            return Err(MeshError::Range(msg))
+        }
+
+        // Indices that don't fit in `u16` promote the storage to `u32`, the whole buffer is uploaded again.
+        if let Indices::U16(current) = &self.indices {
+            if indices.iter().any(|i| *i > u16::MAX as u32) {
+                self.indices = Indices::U32(current.iter().map(|i| *i as u32).collect());
+                self.index_capacity = 0;
+                merge_dirty(&mut self.dirty_indices, 0..self.indices.len());
+            }
+        }
+
+        match &mut self.indices {
+            Indices::None => {},
+            Indices::U16(current) => {
+                for (c, i) in current[start..end].iter_mut().zip(indices) {
+                    *c = *i as u16;
+                }
+            },
+            Indices::U32(current) => current[start..end].copy_from_slice(indices),
+        }
+
+        merge_dirty(&mut self.dirty_indices, start..end);
+        Ok(())
+    }
+
+    /// Upload the modified vertices and indices to the GPU.
+    ///
+    /// Called by `RenderSys` for the dirty meshes, in any context since buffers are shared.
+    /// The GPU buffers are created by the first upload with vertices.
+    pub fn upload(&mut self) {
+        let usage = if self.is_dynamic { gl::DYNAMIC_DRAW } else { gl::STATIC_DRAW };
+
+        if self.vbo.is_null() {
+            if self.vertices.len() == 0 {
+                return
+            }
+
+            self.vbo = GlObject::generate(GlObjectKind::Buffer);
+            self.ebo = GlObject::generate(GlObjectKind::Buffer);
+            self.dirty_vertices = Some(0..self.vertices.len());
+            self.dirty_indices = Some(0..self.indices.len());
+        }
+
+        unsafe {
+            if let Some(dirty) = self.dirty_vertices.take() {
+                gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo.get_id());
+                upload_buffer(gl::ARRAY_BUFFER, &mut self.vertex_capacity, dirty, self.vertices.len(), self.layout.get_stride(), self.vertices.as_ptr(), usage);
+            }
+
+            if let Some(dirty) = self.dirty_indices.take() {
+                if let Some((_, size)) = self.indices.gl_type() {
+                    // The element array binding belongs to a vertex array, the
+                    // indices are uploaded through the array buffer binding instead.
+                    gl::BindBuffer(gl::ARRAY_BUFFER, self.ebo.get_id());
+                    upload_buffer(gl::ARRAY_BUFFER, &mut self.index_capacity, dirty, self.indices.len(), size, self.indices.as_ptr(), usage);
+                }
+            }
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    /// Create the vertex array of the current context, if the
+    /// mesh has buffers and wasn't drawn in this context yet.
+    ///
+    /// Called by `RenderSys` before drawing into each window.
+    pub fn prepare_context(&mut self) {
+        let context = object::current_context();
+
+        if self.vbo.is_null() || self.vaos.iter().any(|vao| vao.get_context() == context) {
+            return
+        }
+
+        let vao = GlObject::generate(GlObjectKind::VertexArray);
+
+        unsafe {
+            gl::BindVertexArray(vao.get_id());
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo.get_id());
+
+            self.layout.apply();
+
+            // The index buffer binding is part of the vertex array.
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo.get_id());
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+
+        self.vaos.push(vao);
+    }
+
+    /// Delete the vertex array of the current context, before the context is destroyed.
+    ///
+    /// Called by `WindowSys` when a window is closed.
+    pub fn release_context(&mut self) {
+        let context = object::current_context();
+        self.vaos.retain(|vao| vao.get_context() != context);
+    }
+
+    /// Draw the mesh, its material must be applied first (see `Material::apply`).
+    ///
+    /// Nothing is drawn until `prepare_context` was called in the current context.
     pub fn draw(&self) {
+        let count = self.element_count();
+        let range = match &self.draw_range {
+            Some(range) => range.start.min(count)..range.end.min(count),
+            None => 0..count,
+        };
+
+        let context = object::current_context();
+
+        let vao = match self.vaos.iter().find(|vao| vao.get_context() == context) {
+            Some(vao) if range.start < range.end => vao,
+            _ => return,
+        };
+
+        let mode = self.topology.gl_enum();
+        let len = (range.end - range.start) as i32;
+
         unsafe {
-            self.shader.use_it();
-            gl::BindVertexArray(self.vao);
-            gl::DrawElements(gl::TRIANGLES, self.indices.len() as i32 * 3, gl::UNSIGNED_SHORT, std::ptr::null());
+            gl::BindVertexArray(vao.get_id());
+
+            match self.indices.gl_type() {
+                Some((gl_type, size)) => gl::DrawElements(mode, len, gl_type, (range.start * size) as *const GLvoid),
+                None => gl::DrawArrays(mode, range.start as i32, len),
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gl::{shader::Shader, vertex::AttributeInfo};
+    use cgmath::Vector4;
+
+    /// A vertex without the `uv` attribute of the test material.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct ColorVertex {
+        pos: Vector2<f32>,
+        color: Vector4<f32>,
+    }
+
+    crate::impl_vertex!(ColorVertex { pos => 0, color => 1 });
+
+    fn material() -> Rc<Material> {
+        let attributes = vec![
+            AttributeInfo { name: String::from("pos"), gl_type: gl::FLOAT_VEC2, location: 0 },
+            AttributeInfo { name: String::from("uv"), gl_type: gl::FLOAT_VEC2, location: 2 },
+        ];
+
+        Rc::new(Material::new(Rc::new(Shader::headless(Vec::new(), attributes))))
+    }
+
+    fn vertices(count: usize) -> Vec<Vertex2D> {
+        (0..count)
+            .map(|i| Vertex2D::new(Vector2::new(i as f32, (i * i) as f32), Vector2::new(0.0, 1.0), Vector2::new(0.0, 0.0)))
+            .collect()
+    }
+
+    fn mesh(vertex_count: usize, indices: Vec<u32>) -> Mesh2D {
+        let indices = Indices::U16(indices.into_iter().map(|i| i as u16).collect());
+        Mesh2D::create(vertices(vertex_count), indices, Topology::Triangles, material(), true).unwrap()
+    }
+
+    #[test]
+    fn update_indices_promotes_u16_storage() {
+        let mut mesh = mesh(70001, vec![0, 1, 2, 1, 2, 3]);
+        mesh.index_capacity = 6;
+        mesh.dirty_indices = None;
+
+        mesh.update_indices(3, &[70000, 1, 2]).unwrap();
+
+        assert_eq!(mesh.get_indices(), &Indices::U32(vec![0, 1, 2, 70000, 1, 2]));
+        assert_eq!(mesh.index_capacity, 0);
+        assert_eq!(mesh.dirty_indices, Some(0..6));
+    }
+
+    #[test]
+    fn update_indices_keeps_u16_when_they_fit() {
+        let mut mesh = mesh(8, vec![0, 1, 2, 1, 2, 3]);
+        mesh.dirty_indices = None;
+
+        mesh.update_indices(3, &[5, 6, 7]).unwrap();
+
+        assert_eq!(mesh.get_indices(), &Indices::U16(vec![0, 1, 2, 5, 6, 7]));
+        assert_eq!(mesh.dirty_indices, Some(3..6));
+    }
+
+    #[test]
+    fn update_indices_rejects_out_of_range() {
+        let mut mesh = mesh(4, vec![0, 1, 2]);
+
+        assert!(matches!(mesh.update_indices(0, &[0, 1, 4]), Err(MeshError::Range(_))));
+        assert!(matches!(mesh.update_indices(2, &[0, 1]), Err(MeshError::Range(_))));
+    }
+
+    #[test]
+    fn incompatible_layout_is_rejected() {
+        let attributes = vec![AttributeInfo { name: String::from("color"), gl_type: gl::FLOAT_VEC4, location: 3 }];
+        let material = Rc::new(Material::new(Rc::new(Shader::headless(Vec::new(), attributes))));
+
+        assert!(matches!(Mesh2D::empty::<Vertex2D>(Topology::Triangles, material, false), Err(MeshError::Layout(_))));
+    }
+
+    #[test]
+    fn empty_mesh_is_filled_by_set_geometry() {
+        let mut mesh = Mesh2D::empty::<Vertex2D>(Topology::Triangles, material(), true).unwrap();
+
+        // A quad can't be set one part after the other: 4 vertices without indices
+        // aren't whole triangles, and the indices refer to vertices not set yet.
+        assert!(matches!(mesh.set_vertices(vertices(4)), Err(MeshError::Topology(_))));
+        assert!(matches!(mesh.set_indices(vec![0u16, 1, 2, 0, 2, 3]), Err(MeshError::Range(_))));
+
+        mesh.set_geometry(vertices(4), vec![0u16, 1, 2, 0, 2, 3]).unwrap();
+
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.element_count(), 6);
+        assert_eq!(mesh.dirty_vertices, Some(0..4));
+        assert_eq!(mesh.dirty_indices, Some(0..6));
+    }
+
+    #[test]
+    fn set_geometry_checks_indices_against_new_vertices() {
+        let mut mesh = mesh(4, vec![0, 1, 2, 0, 2, 3]);
+
+        assert!(matches!(mesh.set_geometry(vertices(3), vec![0u16, 1, 3]), Err(MeshError::Range(_))));
+        assert!(matches!(mesh.set_geometry(vertices(4), vec![0u16, 1]), Err(MeshError::Topology(_))));
+        assert!(matches!(mesh.set_geometry(vec![ColorVertex { pos: Vector2::new(0.0, 0.0), color: Vector4::new(1.0, 1.0, 1.0, 1.0) }; 3], Indices::None), Err(MeshError::Layout(_))));
+
+        // A failed call leaves the mesh as it was.
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.element_count(), 6);
+
+        mesh.set_geometry(vertices(3), Indices::None).unwrap();
+        assert_eq!(mesh.get_indices(), &Indices::None);
+        assert_eq!(mesh.element_count(), 3);
+    }
+
+    #[test]
+    fn degenerate_tolerance_scales_with_the_mesh() {
+        let triangles = |scale: f32| {
+            let points = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (2.0, 0.0), (3.0, 0.0)];
+            let vertices = points.iter()
+                .map(|(x, y)| Vertex2D::new(Vector2::new(x * scale, y * scale), Vector2::new(0.0, 1.0), Vector2::new(0.0, 0.0)))
+                .collect();
+
+            // A valid triangle, a flat one and one with a repeated index.
+            let indices = Indices::U32(vec![0, 1, 2, 1, 3, 4, 0, 2, 2]);
+            Mesh2D::create(vertices, indices, Topology::Triangles, material(), false).unwrap().degenerate_triangles()
+        };
+
+        assert_eq!(triangles(1.0), vec![1, 2]);
+        assert_eq!(triangles(1e-4), vec![1, 2]);
+        assert_eq!(triangles(1e4), vec![1, 2]);
+    }
+}
@@ -4,4 +4,16 @@ pub mod tags;
 pub mod window;
 pub mod smc;
 pub mod mesh_2d;
-pub mod transform2d;
\ No newline at end of file
+pub mod transform2d;
+pub mod sprite;
+pub mod sprite_animation;
+pub mod time;
+pub mod camera2d;
+pub mod input;
+pub mod action_map;
+pub mod gamepads;
+pub mod input_replay;
+pub mod window_config;
+pub mod shader_watcher;
+pub mod debug_draw;
+pub mod render_errors;
@@ -0,0 +1,92 @@
+use std::{
+    collections::HashSet,
+    fmt::Display,
+};
+
+use crate::core::world::{EntityComponentManager, EntitySelector};
+
+/// The errors of the systems that draw, taken by the application.
+///
+/// Drawing keeps going when something is wrong (an uniform value that
+/// doesn't match the shader, ...), the error is reported here instead.
+/// Each distinct error is reported once, so an error repeated every
+/// frame doesn't pile up.
+pub struct RenderErrors {
+    /// The errors not taken yet, oldest first.
+    pending: Vec<String>,
+
+    /// Every error reported so far.
+    reported: HashSet<String>,
+}
+
+impl Default for RenderErrors {
+    fn default() -> Self {
+        RenderErrors::new()
+    }
+}
+
+impl RenderErrors {
+    /// Create new `RenderErrors` without errors.
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    /// Report an error, `false` if it was already reported.
+    /// # Arguments
+    /// * `error` - The error.
+    pub fn report(&mut self, error: impl Display) -> bool {
+        let error = error.to_string();
+
+        if !self.reported.insert(error.clone()) {
+            return false
+        }
+
+        self.pending.push(error);
+        true
+    }
+
+    /// Take the errors reported since the previous call, oldest first.
+    pub fn take(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Report an error to the `RenderErrors` of a world, created if needed.
+    /// # Arguments
+    /// * `world` - The world.
+    /// * `error` - The error.
+    pub fn report_to(world: &mut EntityComponentManager, error: impl Display) {
+        let entity = match <(RenderErrors,)>::query_first_from(world) {
+            Some(entity) => entity,
+            None => {
+                let entity = world.create_entity().unwrap();
+                world.add_component(&entity, RenderErrors::new());
+                entity
+            },
+        };
+
+        world.get_component_mut::<RenderErrors>(&entity).unwrap().report(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_each_error_once() {
+        let mut errors = RenderErrors::new();
+
+        assert!(errors.report("no uniform 'model'"));
+        assert!(errors.report("no uniform 'time'"));
+        assert!(!errors.report("no uniform 'model'"));
+
+        assert_eq!(errors.take(), vec![String::from("no uniform 'model'"), String::from("no uniform 'time'")]);
+        assert!(errors.take().is_empty());
+
+        assert!(!errors.report("no uniform 'time'"));
+        assert!(errors.take().is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    path::PathBuf,
+    rc::{Rc, Weak},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::core::gl::shader::{Shader, ShaderError};
+
+/// Sent by `ShaderReloadSys` for each shader whose files changed.
+///
+/// The events of a frame stay readable until the next update of `ShaderReloadSys`.
+pub struct ShaderReloaded {
+    /// The source files of the shader.
+    pub paths: Vec<PathBuf>,
+
+    /// Why the shader kept its current program, `None` if it was reloaded.
+    pub error: Option<ShaderError>,
+}
+
+/// A watched shader and the modification time of its files.
+struct WatchedShader {
+    shader: Weak<Shader>,
+    modified: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl WatchedShader {
+    fn new(shader: &Rc<Shader>) -> Self {
+        Self {
+            shader: Rc::downgrade(shader),
+            modified: modification_times(shader),
+        }
+    }
+}
+
+/// The modification time of the source files of a shader, `None` if a file can't be read.
+fn modification_times(shader: &Shader) -> Vec<(PathBuf, Option<SystemTime>)> {
+    shader.get_source_paths().iter().map(|path| {
+        (path.clone(), std::fs::metadata(path).and_then(|m| m.modified()).ok())
+    }).collect()
+}
+
+/// The shaders reloaded by `ShaderReloadSys` when their files change.
+///
+/// Shaders are held weakly, a dropped shader is no longer watched.
+pub struct ShaderWatcher {
+    /// The time between two checks of the files.
+    pub interval: Duration,
+
+    /// When the files were checked for the last time.
+    last_poll: Instant,
+
+    /// The watched shaders.
+    shaders: Vec<WatchedShader>,
+}
+
+impl ShaderWatcher {
+    /// Create new `ShaderWatcher` without shaders.
+    /// # Arguments
+    /// * `interval` - The time between two checks of the files.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_poll: Instant::now(),
+            shaders: Vec::new(),
+        }
+    }
+
+    /// Watch the files of a shader, does nothing if the shader is already watched.
+    /// # Arguments
+    /// * `shader` - The shader.
+    pub fn watch(&mut self, shader: &Rc<Shader>) {
+        let ptr = Rc::as_ptr(shader);
+
+        if !self.shaders.iter().any(|w| w.shader.as_ptr() == ptr) {
+            self.shaders.push(WatchedShader::new(shader));
+        }
+    }
+
+    /// The amount of watched shaders.
+    pub fn len(&self) -> usize {
+        self.shaders.len()
+    }
+
+    /// `true` if no shader is watched.
+    pub fn is_empty(&self) -> bool {
+        self.shaders.is_empty()
+    }
+
+    /// `true` if the files must be checked, once per `interval`.
+    pub fn should_poll(&self) -> bool {
+        self.last_poll.elapsed() >= self.interval
+    }
+
+    /// Get the shaders whose files changed since the previous poll.
+    ///
+    /// Forget the dropped shaders. The new modification times are stored
+    /// right away, so a shader that fails to reload is not returned again
+    /// until its files change again.
+    pub fn poll(&mut self) -> Vec<Rc<Shader>> {
+        self.last_poll = Instant::now();
+        self.shaders.retain(|w| w.shader.strong_count() > 0);
+
+        let mut changed = Vec::new();
+
+        for watched in self.shaders.iter_mut() {
+            let shader = watched.shader.upgrade().unwrap();
+            let modified = modification_times(&shader);
+
+            if modified != watched.modified {
+                watched.modified = modified;
+                changed.push(shader);
+            }
+        }
+
+        changed
+    }
+
+    /// Store the modification times of the files of a shader again,
+    /// its files may have changed (a new `#include`) after a reload.
+    /// # Arguments
+    /// * `shader` - The shader.
+    pub fn refresh(&mut self, shader: &Rc<Shader>) {
+        let ptr = Rc::as_ptr(shader);
+
+        if let Some(watched) = self.shaders.iter_mut().find(|w| w.shader.as_ptr() == ptr) {
+            watched.modified = modification_times(shader);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, path::Path};
+    use crate::core::temp_dir::TempDir;
+
+    /// Move the modification time of a file, as if it was saved again.
+    fn touch(path: &Path, seconds: u64) {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + seconds);
+        File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    fn shader(files: &[&PathBuf]) -> Rc<Shader> {
+        Rc::new(Shader::headless(Vec::new(), Vec::new()).with_source_paths(files.iter().map(|f| (*f).clone()).collect()))
+    }
+
+    #[test]
+    fn poll_returns_changed_shaders() {
+        let dir = TempDir::new("watcher-poll");
+        let (vert, frag, other) = (dir.write("vert.glsl", "void main() {}"), dir.write("frag.glsl", "void main() {}"), dir.write("other.glsl", "void main() {}"));
+        let (shader, unchanged) = (shader(&[&vert, &frag]), shader(&[&other]));
+
+        let mut watcher = ShaderWatcher::new(Duration::from_secs(0));
+        watcher.watch(&shader);
+        watcher.watch(&shader);
+        watcher.watch(&unchanged);
+
+        assert_eq!(watcher.len(), 2);
+        assert!(watcher.should_poll());
+        assert!(watcher.poll().is_empty());
+
+        touch(&frag, 1);
+        let changed = watcher.poll();
+        assert_eq!(changed.len(), 1);
+        assert!(Rc::ptr_eq(&changed[0], &shader));
+
+        // The new times are stored, even if the shader isn't reloaded.
+        assert!(watcher.poll().is_empty());
+
+        std::fs::remove_file(&vert).unwrap();
+        assert_eq!(watcher.poll().len(), 1);
+        assert!(watcher.poll().is_empty());
+    }
+
+    #[test]
+    fn refresh_and_dropped_shaders() {
+        let dir = TempDir::new("watcher-refresh");
+        let file = dir.write("shader.glsl", "void main() {}");
+        let shader = shader(&[&file]);
+
+        let mut watcher = ShaderWatcher::new(Duration::from_secs(3600));
+        watcher.watch(&shader);
+        assert!(!watcher.should_poll());
+
+        // A change already seen by a refresh (after a reload) isn't returned.
+        touch(&file, 2);
+        watcher.refresh(&shader);
+        assert!(watcher.poll().is_empty());
+
+        drop(shader);
+        assert!(watcher.poll().is_empty());
+        assert!(watcher.is_empty());
+    }
+}
@@ -1,4 +1,12 @@
-
 pub struct SystemManagerComponent {
-    pub shutdown: bool
-}
\ No newline at end of file
+    pub shutdown: bool,
+
+    /// The code returned by `World::start_loop` once shut down.
+    pub exit_code: i32,
+}
+
+/// Send this event to shut down the world at the end of the frame.
+///
+/// When several `AppExit` are sent during the same frame the first one wins.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AppExit(pub i32);
\ No newline at end of file
@@ -0,0 +1,163 @@
+
+use std::rc::Rc;
+
+use cgmath::{
+    Vector2,
+    Vector4,
+};
+
+use crate::components::color::Color;
+use crate::core::{
+    rect::Rect,
+    gl::{
+        shader::Shader,
+        texture::Texture,
+        sprite_batch::SpriteVertex,
+    },
+};
+
+/// A textured quad rendered through the sprite batch.
+///
+/// Sprites that share the same texture and shader
+/// are drawn together with a single draw call.
+pub struct Sprite {
+    /// The texture the sprite is cut from.
+    pub texture: Rc<Texture>,
+
+    /// The shader used to draw the sprite.
+    pub shader: Rc<Shader>,
+
+    /// The region of the texture to draw, in uv coordinates.
+    pub region: Rect,
+
+    /// The sprite size in world units.
+    pub size: Vector2<f32>,
+
+    /// The tint multiplied with the texture color.
+    pub color: Color,
+
+    /// Mirror the sprite horizontally.
+    pub flip_x: bool,
+
+    /// Mirror the sprite vertically.
+    pub flip_y: bool,
+
+    /// The pivot of the sprite, from `(0, 0)` (bottom left)
+    /// to `(1, 1)` (top right).
+    pub anchor: Vector2<f32>,
+}
+
+impl Sprite {
+    /// Create new `Sprite` that display the whole texture.
+    /// # Arguments
+    /// * `texture` - The texture of the sprite.
+    /// * `shader` - The shader used to draw the sprite.
+    /// * `size` - The sprite size in world units.
+    pub fn new(texture: Rc<Texture>, shader: Rc<Shader>, size: Vector2<f32>) -> Self {
+        Self {
+            texture,
+            shader,
+            size,
+            region: Rect::new(0.0, 0.0, 1.0, 1.0),
+            color:  Color(1.0, 1.0, 1.0, 1.0),
+            flip_x: false,
+            flip_y: false,
+            anchor: Vector2::new(0.5, 0.5),
+        }
+    }
+
+    /// Build the four vertices of the sprite quad.
+    /// # Arguments
+    /// * `pos` - The world position of the sprite anchor.
+    pub fn vertices(&self, pos: Vector2<f32>) -> [SpriteVertex; 4] {
+        let min = Vector2::new(pos.x - self.anchor.x * self.size.x, pos.y - self.anchor.y * self.size.y);
+        let max = min + self.size;
+
+        let (mut u0, mut u1) = (self.region.x, self.region.x + self.region.w);
+        let (mut v0, mut v1) = (self.region.y, self.region.y + self.region.h);
+
+        if self.flip_x { std::mem::swap(&mut u0, &mut u1); }
+        if self.flip_y { std::mem::swap(&mut v0, &mut v1); }
+
+        let color = Vector4::new(self.color.0, self.color.1, self.color.2, self.color.3);
+
+        [
+            SpriteVertex::new(Vector2::new(min.x, min.y), Vector2::new(u0, v0), color),
+            SpriteVertex::new(Vector2::new(min.x, max.y), Vector2::new(u0, v1), color),
+            SpriteVertex::new(Vector2::new(max.x, max.y), Vector2::new(u1, v1), color),
+            SpriteVertex::new(Vector2::new(max.x, min.y), Vector2::new(u1, v0), color),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprite() -> Sprite {
+        let shader = Rc::new(Shader::headless(Vec::new(), Vec::new()));
+        Sprite::new(Rc::new(Texture::headless(32, 32)), shader, Vector2::new(4.0, 2.0))
+    }
+
+    fn positions(vertices: &[SpriteVertex; 4]) -> Vec<(f32, f32)> {
+        vertices.iter().map(|v| (v.pos.x, v.pos.y)).collect()
+    }
+
+    fn uvs(vertices: &[SpriteVertex; 4]) -> Vec<(f32, f32)> {
+        vertices.iter().map(|v| (v.uv.x, v.uv.y)).collect()
+    }
+
+    #[test]
+    fn quad_is_centered_on_the_default_anchor() {
+        let vertices = sprite().vertices(Vector2::new(10.0, 20.0));
+
+        assert_eq!(positions(&vertices), vec![(8.0, 19.0), (8.0, 21.0), (12.0, 21.0), (12.0, 19.0)]);
+        assert_eq!(uvs(&vertices), vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]);
+    }
+
+    #[test]
+    fn anchor_moves_the_quad() {
+        let mut sprite = sprite();
+
+        sprite.anchor = Vector2::new(0.0, 0.0);
+        assert_eq!(positions(&sprite.vertices(Vector2::new(1.0, 1.0))), vec![(1.0, 1.0), (1.0, 3.0), (5.0, 3.0), (5.0, 1.0)]);
+
+        sprite.anchor = Vector2::new(1.0, 1.0);
+        assert_eq!(positions(&sprite.vertices(Vector2::new(1.0, 1.0))), vec![(-3.0, -1.0), (-3.0, 1.0), (1.0, 1.0), (1.0, -1.0)]);
+    }
+
+    #[test]
+    fn region_sets_the_uvs() {
+        let mut sprite = sprite();
+        sprite.region = Rect::new(0.25, 0.5, 0.25, 0.5);
+
+        let vertices = sprite.vertices(Vector2::new(0.0, 0.0));
+        assert_eq!(uvs(&vertices), vec![(0.25, 0.5), (0.25, 1.0), (0.5, 1.0), (0.5, 0.5)]);
+    }
+
+    #[test]
+    fn flips_swap_the_uvs_not_the_positions() {
+        let mut sprite = sprite();
+        sprite.region = Rect::new(0.25, 0.5, 0.25, 0.5);
+        let unflipped = positions(&sprite.vertices(Vector2::new(0.0, 0.0)));
+
+        sprite.flip_x = true;
+        let vertices = sprite.vertices(Vector2::new(0.0, 0.0));
+        assert_eq!(positions(&vertices), unflipped);
+        assert_eq!(uvs(&vertices), vec![(0.5, 0.5), (0.5, 1.0), (0.25, 1.0), (0.25, 0.5)]);
+
+        sprite.flip_y = true;
+        let vertices = sprite.vertices(Vector2::new(0.0, 0.0));
+        assert_eq!(uvs(&vertices), vec![(0.5, 1.0), (0.5, 0.5), (0.25, 0.5), (0.25, 1.0)]);
+    }
+
+    #[test]
+    fn color_is_copied_to_every_vertex() {
+        let mut sprite = sprite();
+        sprite.color = Color(0.1, 0.2, 0.3, 0.4);
+
+        for vertex in sprite.vertices(Vector2::new(0.0, 0.0)).iter() {
+            assert_eq!(vertex.color, Vector4::new(0.1, 0.2, 0.3, 0.4));
+        }
+    }
+}
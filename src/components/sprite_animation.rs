@@ -0,0 +1,208 @@
+
+use std::rc::Rc;
+
+use crate::core::{
+    rect::Rect,
+    sprite_sheet::{AnimationEvent, LoopMode, SpriteSheet},
+};
+
+/// Play the clips of a sprite sheet on the `Sprite` of the same entity.
+pub struct SpriteAnimation {
+    /// The sprite sheet that contains the clips.
+    pub sheet: Rc<SpriteSheet>,
+
+    /// The name of the clip being played.
+    pub clip: String,
+
+    /// The current frame index in the clip.
+    pub frame: usize,
+
+    /// The time (in seconds) spent on the current frame.
+    pub elapsed: f32,
+
+    /// The playback speed multiplier.
+    pub speed: f32,
+
+    /// `false` when the animation is paused or finished.
+    pub playing: bool,
+
+    /// The events fired during the last update.
+    pub events: Vec<String>,
+
+    /// `1` when playing forward, `-1` when playing backward (ping pong).
+    direction: i32,
+
+    /// `true` when the events of the current frame must be fired.
+    entering: bool,
+}
+
+impl SpriteAnimation {
+    /// Create new `SpriteAnimation` that play a clip.
+    /// # Arguments
+    /// * `sheet` - The sprite sheet that contains the clips.
+    /// * `clip` - The name of the clip to play.
+    pub fn new(sheet: Rc<SpriteSheet>, clip: &str) -> Self {
+        Self {
+            sheet,
+            clip: String::from(clip),
+            frame: 0,
+            elapsed: 0.0,
+            speed: 1.0,
+            playing: true,
+            events: Vec::new(),
+            direction: 1,
+            entering: true,
+        }
+    }
+
+    /// Play a clip from its first frame.
+    /// # Arguments
+    /// * `clip` - The name of the clip to play.
+    pub fn play(&mut self, clip: &str) {
+        self.clip = String::from(clip);
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.playing = true;
+        self.direction = 1;
+        self.entering = true;
+    }
+
+    /// The region (in uv coordinates) of the current frame.
+    pub fn current_region(&self) -> Option<Rect> {
+        let clip = self.sheet.clips.get(&self.clip)?;
+        self.sheet.frames.get(*clip.frames.get(self.frame)?).copied()
+    }
+
+    /// Move the animation forward in time.
+    /// # Arguments
+    /// * `delta` - The time (in seconds) elapsed since the previous update.
+    pub fn advance(&mut self, delta: f32) {
+        self.events.clear();
+
+        let sheet = self.sheet.clone();
+        let clip = match sheet.clips.get(&self.clip) {
+            Some(clip) if !clip.is_empty() => clip,
+            _ => return,
+        };
+
+        if !self.playing {
+            return
+        }
+
+        if self.entering {
+            self.entering = false;
+            self.fire_events(&clip.events);
+        }
+
+        self.elapsed += delta * self.speed;
+
+        loop {
+            // The fields of a clip are public, the durations may not match the frames.
+            let duration = match clip.durations.get(self.frame) {
+                Some(duration) if *duration > 0.0 && self.elapsed >= *duration => *duration,
+                _ => return,
+            };
+
+            self.elapsed -= duration;
+
+            let last = clip.len() - 1;
+
+            match clip.mode {
+                LoopMode::Once if self.frame == last => {
+                    self.elapsed = 0.0;
+                    self.playing = false;
+                    return
+                },
+                LoopMode::Once => self.frame += 1,
+                LoopMode::Loop => self.frame = (self.frame + 1) % clip.len(),
+                LoopMode::PingPong => {
+                    if last == 0 {
+                        return
+                    }
+
+                    let next = self.frame as i32 + self.direction;
+                    if next < 0 || next > last as i32 {
+                        self.direction = -self.direction;
+                    }
+
+                    self.frame = (self.frame as i32 + self.direction) as usize;
+                },
+            }
+
+            self.fire_events(&clip.events);
+        }
+    }
+
+    /// Fire the events of the current frame.
+    fn fire_events(&mut self, events: &[AnimationEvent]) {
+        let frame = self.frame;
+
+        for event in events.iter().filter(|e| e.frame == frame) {
+            self.events.push(event.name.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{gl::texture::Texture, sprite_sheet::AnimationClip};
+
+    /// An animation playing a clip of a 4x1 sheet.
+    fn animation(clip: AnimationClip) -> SpriteAnimation {
+        let mut sheet = SpriteSheet::from_grid(Rc::new(Texture::headless(64, 16)), 4, 1);
+        sheet.add_clip("clip", clip);
+        SpriteAnimation::new(Rc::new(sheet), "clip")
+    }
+
+    /// The frames reached after each update.
+    fn frames(animation: &mut SpriteAnimation, delta: f32, updates: usize) -> Vec<usize> {
+        (0..updates).map(|_| { animation.advance(delta); animation.frame }).collect()
+    }
+
+    #[test]
+    fn loop_modes() {
+        let mut once = animation(AnimationClip::new(vec![0, 1, 2], 1.0, LoopMode::Once).unwrap());
+        assert_eq!(frames(&mut once, 1.0, 4), vec![1, 2, 2, 2]);
+        assert!(!once.playing);
+
+        let mut looping = animation(AnimationClip::new(vec![0, 1, 2], 1.0, LoopMode::Loop).unwrap());
+        assert_eq!(frames(&mut looping, 1.0, 4), vec![1, 2, 0, 1]);
+
+        let mut ping_pong = animation(AnimationClip::new(vec![0, 1, 2], 1.0, LoopMode::PingPong).unwrap());
+        assert_eq!(frames(&mut ping_pong, 1.0, 6), vec![1, 2, 1, 0, 1, 2]);
+
+        // Several frames in a single update.
+        let mut fast = animation(AnimationClip::new(vec![0, 1, 2, 3], 0.25, LoopMode::Loop).unwrap());
+        assert_eq!(frames(&mut fast, 0.5, 2), vec![2, 0]);
+    }
+
+    #[test]
+    fn fires_frame_events() {
+        let clip = AnimationClip::new(vec![0, 1], 1.0, LoopMode::Loop).unwrap().with_event(0, "start").with_event(1, "step");
+        let mut animation = animation(clip);
+
+        animation.advance(0.5);
+        assert_eq!(animation.events, vec![String::from("start")]);
+
+        animation.advance(0.5);
+        assert_eq!(animation.events, vec![String::from("step")]);
+
+        animation.advance(0.5);
+        assert!(animation.events.is_empty());
+    }
+
+    #[test]
+    fn mismatched_durations_dont_panic() {
+        let mut clip = AnimationClip::new(vec![0, 1, 2], 1.0, LoopMode::Loop).unwrap();
+        clip.durations.truncate(1);
+
+        let mut mismatched = animation(clip);
+        assert_eq!(frames(&mut mismatched, 1.0, 3), vec![1, 1, 1]);
+        assert!(mismatched.current_region().is_some());
+
+        // A zero duration holds the frame.
+        let mut still = animation(AnimationClip::new(vec![0, 1], 0.0, LoopMode::Loop).unwrap());
+        assert_eq!(frames(&mut still, 1.0, 2), vec![0, 0]);
+    }
+}
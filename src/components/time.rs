@@ -0,0 +1,52 @@
+
+use std::time::Instant;
+
+/// The frame timing of the world.
+pub struct Time {
+    /// The time (in seconds) elapsed since the previous frame.
+    pub delta: f32,
+
+    /// The time (in seconds) elapsed since the first frame.
+    pub elapsed: f32,
+
+    /// The amount of frames since the first frame.
+    pub frame: u64,
+
+    /// When the previous frame started.
+    last_instant: Instant,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::new()
+    }
+}
+
+impl Time {
+    /// Create new `Time`
+    pub fn new() -> Self {
+        Self {
+            delta: 0.0,
+            elapsed: 0.0,
+            frame: 0,
+            last_instant: Instant::now(),
+        }
+    }
+
+    /// Start a new frame, measuring the delta time with the system clock.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_instant).as_secs_f32();
+        self.last_instant = now;
+        self.advance(delta);
+    }
+
+    /// Start a new frame with a fixed delta time.
+    /// # Arguments
+    /// * `delta` - The time (in seconds) elapsed since the previous frame.
+    pub fn advance(&mut self, delta: f32) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.frame += 1;
+    }
+}
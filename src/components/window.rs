@@ -1,10 +1,321 @@
 extern crate glfw;
 use std::sync::mpsc::Receiver;
 
+use cgmath::Vector2;
+use glfw::Context;
+
+use crate::components::window_config::{CursorMode, WindowConfig, WindowMode};
+use crate::core::{
+    entity::Entity,
+    gl::{object, texture::TextureError},
+    platform_event::PlatformEvent,
+    world::{EntityComponentManager, EntitySelector},
+};
+
+/// Sent with every `PlatformEvent` to tell which window received it.
+#[derive(Clone)]
+pub struct WindowEvent {
+    /// The window entity.
+    pub window: Entity,
+
+    /// The event.
+    pub event: PlatformEvent,
+}
+
+/// Sent when a window was closed and its entity destroyed.
+#[derive(Clone, Copy)]
+pub struct WindowClosed {
+    /// The destroyed window entity.
+    pub window: Entity,
+}
+
+/// Send this event to let `WindowSys` open a new window.
+///
+/// The new window share its GL objects (buffers, textures, shaders)
+/// with the other windows.
+#[derive(Clone)]
+pub struct OpenWindow(pub WindowConfig);
+
+/// Sent when the size or the scale of a window change.
+pub struct WindowResized {
+    /// The window entity.
+    pub window: Entity,
+
+    /// The new window size in screen coordinates.
+    pub size: (u32, u32),
+
+    /// The new framebuffer size in pixels.
+    pub framebuffer_size: (u32, u32),
+
+    /// The new content scale (DPI scale) of the window.
+    pub content_scale: (f32, f32),
+}
+
 pub struct Window {
+    /// The window size in screen coordinates.
     pub size: (u32, u32),
+
+    /// The framebuffer size in pixels. Differs from `size`
+    /// on HiDPI screens where one screen coordinate is
+    /// more than one pixel.
+    pub framebuffer_size: (u32, u32),
+
+    /// The content scale (DPI scale) of the window.
+    pub content_scale: (f32, f32),
+
     pub title: String,
+
+    /// The key that close the window when pressed, `None` to disable it.
+    pub close_key: Option<glfw::Key>,
+
+    /// `true` for the first window created by `WindowSys`. Cameras
+    /// without target draw into it.
+    pub primary: bool,
+
+    /// Shut down the world when this window is closed, otherwise
+    /// only the window is destroyed. The primary window is never
+    /// destroyed alone, its close is refused when `false`.
+    pub exit_on_close: bool,
+
+    /// How the window is displayed, change it with `set_mode`.
+    mode: WindowMode,
+
+    /// How the cursor behave, change it with `set_cursor_mode`.
+    cursor_mode: CursorMode,
+
+    /// `true` if the window has borders and a title bar when windowed.
+    decorated: bool,
+
+    /// The position and size to restore when going back to windowed mode.
+    windowed_rect: (i32, i32, u32, u32),
+
     pub event: Receiver<(f64, glfw::WindowEvent)>,
     pub raw: glfw::Window,
     pub glfw: glfw::Glfw,
+}
+
+impl Window {
+
+    /// Create new `Window` component from a GLFW window.
+    /// # Arguments
+    /// * `title` - The window title.
+    /// * `raw` - The GLFW window.
+    /// * `event` - The GLFW events receiver of the window.
+    /// * `glfw` - The GLFW context.
+    pub fn new(title: &str, raw: glfw::Window, event: Receiver<(f64, glfw::WindowEvent)>, glfw: glfw::Glfw) -> Self {
+        let (width, height) = raw.get_size();
+        let (fb_width, fb_height) = raw.get_framebuffer_size();
+        let (x, y) = raw.get_pos();
+
+        Self {
+            size: (width as u32, height as u32),
+            framebuffer_size: (fb_width as u32, fb_height as u32),
+            content_scale: raw.get_content_scale(),
+            title: String::from(title),
+            close_key: Some(glfw::Key::Escape),
+            primary: false,
+            exit_on_close: true,
+            mode: WindowMode::Windowed,
+            cursor_mode: CursorMode::Visible,
+            decorated: true,
+            windowed_rect: (x, y, width as u32, height as u32),
+            event,
+            raw,
+            glfw,
+        }
+    }
+
+    /// Find the primary window entity.
+    pub fn find_primary(world: &EntityComponentManager) -> Option<Entity> {
+        <(Window,)>::query_from(world).into_iter().find(|e| world.get_component::<Window>(e).unwrap().primary)
+    }
+
+    /// Make the GL context of this window current, GL
+    /// calls are applied to the current context only.
+    pub fn make_current(&mut self) {
+        if !self.raw.is_current() {
+            self.raw.make_current();
+        }
+
+        object::set_current_context(self.raw.window_ptr() as usize);
+    }
+
+    /// How the window is displayed.
+    pub fn get_mode(&self) -> WindowMode {
+        self.mode
+    }
+
+    /// Switch between windowed, fullscreen and borderless mode.
+    ///
+    /// Fullscreen and borderless windows use the current video mode of the
+    /// monitor. Nothing happens if the requested monitor doesn't exist.
+    /// # Arguments
+    /// * `mode` - How the window is displayed.
+    pub fn set_mode(&mut self, mode: WindowMode) {
+        if self.mode == WindowMode::Windowed {
+            let (x, y) = self.raw.get_pos();
+            self.windowed_rect = (x, y, self.size.0, self.size.1);
+        }
+
+        let (x, y, width, height) = self.windowed_rect;
+        let decorated = self.decorated;
+        let raw = &mut self.raw;
+
+        let applied = self.glfw.with_connected_monitors_mut(|_, monitors| {
+            let index = match mode {
+                WindowMode::Windowed => {
+                    raw.set_decorated(decorated);
+                    raw.set_monitor(glfw::WindowMode::Windowed, x, y, width, height, None);
+                    return true
+                },
+                WindowMode::Fullscreen { monitor } | WindowMode::Borderless { monitor } => monitor.unwrap_or(0),
+            };
+
+            let (monitor, video_mode) = match monitors.get(index) {
+                Some(monitor) => match monitor.get_video_mode() {
+                    Some(video_mode) => (monitor, video_mode),
+                    None => return false,
+                },
+                None => return false,
+            };
+
+            if let WindowMode::Borderless { .. } = mode {
+                let (mx, my) = monitor.get_pos();
+                raw.set_decorated(false);
+                raw.set_monitor(glfw::WindowMode::Windowed, mx, my, video_mode.width, video_mode.height, None);
+            } else {
+                raw.set_monitor(glfw::WindowMode::FullScreen(monitor), 0, 0, video_mode.width, video_mode.height, Some(video_mode.refresh_rate));
+            }
+
+            true
+        });
+
+        if applied {
+            self.mode = mode;
+        }
+    }
+
+    /// Change the window title.
+    pub fn set_title(&mut self, title: &str) {
+        self.raw.set_title(title);
+        self.title = String::from(title);
+    }
+
+    /// Wait an amount of screen refresh before swapping buffers, `1` enable vsync and `0` disable it.
+    ///
+    /// The swap interval belongs to a context, the window context is made current.
+    pub fn set_swap_interval(&mut self, swap_interval: u32) {
+        self.make_current();
+        self.glfw.set_swap_interval(if swap_interval == 0 {
+            glfw::SwapInterval::None
+        } else {
+            glfw::SwapInterval::Sync(swap_interval)
+        });
+    }
+
+    /// Let (or not) the user resize the window.
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.raw.set_resizable(resizable);
+    }
+
+    /// Show (or not) the window borders and title bar when windowed.
+    pub fn set_decorated(&mut self, decorated: bool) {
+        self.decorated = decorated;
+
+        if self.mode == WindowMode::Windowed {
+            self.raw.set_decorated(decorated);
+        }
+    }
+
+    /// How the cursor behave over the window.
+    pub fn get_cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    /// Change how the cursor behave over the window.
+    pub fn set_cursor_mode(&mut self, cursor_mode: CursorMode) {
+        self.raw.set_cursor_mode(cursor_mode.to_glfw());
+        self.cursor_mode = cursor_mode;
+    }
+
+    /// Keep the cursor inside the window when it is confined.
+    ///
+    /// Returns the cursor position, moved back inside the window if needed.
+    /// # Arguments
+    /// * `x` - The cursor position in screen coordinates.
+    /// * `y` - The cursor position in screen coordinates.
+    pub fn confine_cursor(&mut self, x: f64, y: f64) -> (f64, f64) {
+        if self.cursor_mode != CursorMode::Confined {
+            return (x, y)
+        }
+
+        let max_x = self.size.0.saturating_sub(1) as f64;
+        let max_y = self.size.1.saturating_sub(1) as f64;
+        let (cx, cy) = (x.max(0.0).min(max_x), y.max(0.0).min(max_y));
+
+        if (cx, cy) != (x, y) {
+            self.raw.set_cursor_pos(cx, cy);
+        }
+
+        (cx, cy)
+    }
+
+    /// Change the window icon.
+    /// # Arguments
+    /// * `path` - The path of the icon image.
+    pub fn set_icon(&mut self, path: &str) -> Result<(), TextureError> {
+        let image = image::open(path)?.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        let pixels = image.into_raw()
+            .chunks_exact(4)
+            .map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]]))
+            .collect::<Vec<u32>>();
+
+        self.raw.set_icon_from_pixels(vec![glfw::PixelImage { width, height, pixels }]);
+        Ok(())
+    }
+
+    /// Update the window sizes from a platform event.
+    ///
+    /// Returns a `WindowResized` if the event changed the size or the scale of the window.
+    /// # Arguments
+    /// * `window` - The entity of this window.
+    /// * `event` - The platform event.
+    pub fn apply_resize_event(&mut self, window: Entity, event: &PlatformEvent) -> Option<WindowResized> {
+        match *event {
+            PlatformEvent::Resized { width, height } => {
+                self.size = (width, height);
+            },
+            PlatformEvent::FramebufferResized { width, height } => {
+                self.framebuffer_size = (width, height);
+            },
+            PlatformEvent::ScaleChanged { x, y } => {
+                self.content_scale = (x, y);
+            },
+            _ => return None,
+        }
+
+        Some(WindowResized {
+            window,
+            size: self.size,
+            framebuffer_size: self.framebuffer_size,
+            content_scale: self.content_scale,
+        })
+    }
+
+    /// Convert a position in screen coordinates (like the
+    /// cursor position) into a position in framebuffer pixels.
+    /// # Arguments
+    /// * `pos` - The position in screen coordinates.
+    pub fn to_pixels(&self, pos: Vector2<f32>) -> Vector2<f32> {
+        if self.size.0 == 0 || self.size.1 == 0 {
+            return pos
+        }
+
+        Vector2::new(
+            pos.x * self.framebuffer_size.0 as f32 / self.size.0 as f32,
+            pos.y * self.framebuffer_size.1 as f32 / self.size.1 as f32,
+        )
+    }
 }
\ No newline at end of file
@@ -0,0 +1,179 @@
+
+use glfw::Key;
+
+/// How the window is displayed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WindowMode {
+    /// A regular window.
+    Windowed,
+
+    /// Exclusive fullscreen on a monitor, `None` for the primary monitor.
+    Fullscreen { monitor: Option<usize> },
+
+    /// An undecorated window that cover a monitor, `None` for the primary monitor.
+    Borderless { monitor: Option<usize> },
+}
+
+/// How the cursor behave over the window.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CursorMode {
+    /// The cursor is visible and free.
+    Visible,
+
+    /// The cursor is hidden while over the window.
+    Hidden,
+
+    /// The cursor is hidden and locked to the window, for
+    /// unlimited movements (first person cameras, ...).
+    Locked,
+
+    /// The cursor is visible but can't leave the window.
+    Confined,
+}
+
+impl CursorMode {
+    /// The matching GLFW cursor mode.
+    pub fn to_glfw(self) -> glfw::CursorMode {
+        match self {
+            CursorMode::Visible | CursorMode::Confined => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Locked => glfw::CursorMode::Disabled,
+        }
+    }
+}
+
+/// Everything `WindowSys` needs to create a window.
+#[derive(Clone)]
+pub struct WindowConfig {
+    /// The window title.
+    pub title: String,
+
+    /// The window size in screen coordinates when windowed.
+    pub size: (u32, u32),
+
+    /// How the window is displayed.
+    pub mode: WindowMode,
+
+    /// Wait this amount of screen refresh before swapping buffers,
+    /// `1` enable vsync and `0` disable it.
+    pub swap_interval: u32,
+
+    /// Let the user resize the window.
+    pub resizable: bool,
+
+    /// Show the window borders and title bar.
+    pub decorated: bool,
+
+    /// The amount of samples per pixel for MSAA, `None` to disable it.
+    pub samples: Option<u32>,
+
+    /// The requested OpenGL version (core profile), `(4, 1)` by default.
+    /// Compute shaders need `(4, 3)`, which macOS doesn't support.
+    pub gl_version: (u32, u32),
+
+    /// The path of the window icon image.
+    pub icon: Option<String>,
+
+    /// How the cursor behave over the window.
+    pub cursor: CursorMode,
+
+    /// The key that close the window when pressed, `None` to disable it.
+    pub close_key: Option<Key>,
+
+    /// Shut down the world when the window is closed. When `false`
+    /// only the window is destroyed (the world still shut down once
+    /// every window is closed), except the primary window which is
+    /// never destroyed alone: only its `CloseRequested` event is sent.
+    pub exit_on_close: bool,
+}
+
+impl WindowConfig {
+    /// Create new `WindowConfig` for a resizable window with vsync.
+    /// # Arguments
+    /// * `title` - The window title.
+    /// * `size` - The window size in screen coordinates.
+    pub fn new(title: &str, size: (u32, u32)) -> Self {
+        Self {
+            title: String::from(title),
+            size,
+            mode: WindowMode::Windowed,
+            swap_interval: 1,
+            resizable: true,
+            decorated: true,
+            samples: None,
+            gl_version: (4, 1),
+            icon: None,
+            cursor: CursorMode::Visible,
+            close_key: Some(Key::Escape),
+            exit_on_close: true,
+        }
+    }
+
+    /// Set how the window is displayed.
+    pub fn with_mode(mut self, mode: WindowMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Enable (`true`) or disable (`false`) vsync.
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.swap_interval = vsync as u32;
+        self
+    }
+
+    /// Set the amount of screen refresh to wait before swapping buffers.
+    pub fn with_swap_interval(mut self, swap_interval: u32) -> Self {
+        self.swap_interval = swap_interval;
+        self
+    }
+
+    /// Let (or not) the user resize the window.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Show (or not) the window borders and title bar.
+    pub fn with_decorations(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    /// Set the amount of samples per pixel for MSAA, `None` to disable it.
+    pub fn with_samples(mut self, samples: Option<u32>) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Set the requested OpenGL version (core profile).
+    pub fn with_gl_version(mut self, major: u32, minor: u32) -> Self {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    /// Set the path of the window icon image.
+    ///
+    /// An icon that can't be loaded is reported to `RenderErrors`.
+    pub fn with_icon(mut self, path: &str) -> Self {
+        self.icon = Some(String::from(path));
+        self
+    }
+
+    /// Set how the cursor behave over the window.
+    pub fn with_cursor(mut self, cursor: CursorMode) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    /// Set the key that close the window, `None` to never close the window from the keyboard.
+    pub fn with_close_key(mut self, close_key: Option<Key>) -> Self {
+        self.close_key = close_key;
+        self
+    }
+
+    /// Shut down (or not) the world when the window is closed.
+    pub fn with_exit_on_close(mut self, exit_on_close: bool) -> Self {
+        self.exit_on_close = exit_on_close;
+        self
+    }
+}
@@ -0,0 +1,267 @@
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::core::{
+    rect::Rect,
+    gl::texture::{Texture, TextureError},
+};
+
+/// An image waiting to be packed into an atlas.
+struct AtlasImage {
+    name: String,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+/// Where `AtlasBuilder::pack` put the images.
+struct Packing {
+    /// The atlas width and height in pixels.
+    size: (u32, u32),
+
+    /// The bottom left corner (in pixels) of each image, same order as the images.
+    positions: Vec<(u32, u32)>,
+}
+
+/// Combine many images into a single texture.
+///
+/// Images are packed on shelves sorted by height, the
+/// resulting texture size is the smallest power of two
+/// that fit all of them.
+pub struct AtlasBuilder {
+    /// The images to pack.
+    images: Vec<AtlasImage>,
+
+    /// The maximum width and height of the atlas texture.
+    max_size: u32,
+
+    /// The empty space (in pixels) between two images.
+    padding: u32,
+}
+
+impl AtlasBuilder {
+    /// Create new `AtlasBuilder`
+    /// # Arguments
+    /// * `max_size` - The maximum width and height of the atlas texture.
+    /// * `padding` - The empty space (in pixels) between two images.
+    pub fn new(max_size: u32, padding: u32) -> Self {
+        Self {
+            images: Vec::new(),
+            max_size,
+            padding,
+        }
+    }
+
+    /// Add raw RGBA pixels to the atlas.
+    /// # Arguments
+    /// * `name` - The name used to retrieve the image region.
+    /// * `width` - The image width in pixels.
+    /// * `height` - The image height in pixels.
+    /// * `pixels` - The pixels, 4 bytes per pixel, starting from the bottom left corner.
+    pub fn add_pixels(&mut self, name: &str, width: u32, height: u32, pixels: Vec<u8>) -> Result<(), TextureError> {
+        let size = width as usize * height as usize * 4;
+
+        if pixels.len() != size {
+            let msg = format!("Image '{}' expect {} bytes of pixels but got {}", name, size, pixels.len());
+            return Err(TextureError::new("Size", &msg))
+        }
+
+        self.images.push(AtlasImage { name: String::from(name), width, height, pixels });
+        Ok(())
+    }
+
+    /// Add an image file to the atlas.
+    /// # Arguments
+    /// * `name` - The name used to retrieve the image region.
+    /// * `path` - The path of the image.
+    pub fn add_file(&mut self, name: &str, path: &str) -> Result<(), TextureError> {
+        let image = image::open(path)?.flipv().to_rgba8();
+        let (width, height) = image.dimensions();
+
+        self.add_pixels(name, width, height, image.into_raw())
+    }
+
+    /// Compute the atlas size and the position of each image.
+    fn pack(&self) -> Result<Packing, TextureError> {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by(|a, b| self.images[*b].height.cmp(&self.images[*a].height));
+
+        // In u64, the area of large images overflows an u32.
+        let area: u64 = self.images.iter().map(|i| (i.width + self.padding) as u64 * (i.height + self.padding) as u64).sum();
+        let widest = self.images.iter().map(|i| i.width + self.padding).max().unwrap_or(1);
+
+        let side = ((area as f64).sqrt().ceil() as u64).min(u32::MAX as u64) as u32;
+        let mut next_width = side.max(widest).max(1).checked_next_power_of_two();
+
+        while let Some(width) = next_width.filter(|w| *w <= self.max_size) {
+            let mut positions = vec![(0, 0); self.images.len()];
+            let (mut x, mut y, mut shelf_height) = (0u32, 0u32, 0u32);
+
+            for i in order.iter() {
+                let image = &self.images[*i];
+
+                if x + image.width > width {
+                    x = 0;
+                    y += shelf_height;
+                    shelf_height = 0;
+                }
+
+                positions[*i] = (x, y);
+                x += image.width + self.padding;
+                shelf_height = shelf_height.max(image.height + self.padding);
+            }
+
+            let height = (y + shelf_height).max(1).next_power_of_two();
+
+            if height <= self.max_size {
+                return Ok(Packing { size: (width, height), positions })
+            }
+
+            next_width = width.checked_mul(2);
+        }
+
+        let msg = format!("{} images doesn't fit in a {}x{} atlas", self.images.len(), self.max_size, self.max_size);
+        Err(TextureError::new("Atlas", &msg))
+    }
+
+    /// Pack all images and upload the atlas texture.
+    pub fn build(self) -> Result<TextureAtlas, TextureError> {
+        let Packing { size: (width, height), positions } = self.pack()?;
+
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let mut regions = HashMap::with_capacity(self.images.len());
+
+        for (image, (x, y)) in self.images.iter().zip(positions) {
+            let row_size = image.width as usize * 4;
+
+            for row in 0..image.height as usize {
+                let src = row * row_size;
+                let dst = ((y as usize + row) * width as usize + x as usize) * 4;
+                pixels[dst..dst + row_size].copy_from_slice(&image.pixels[src..src + row_size]);
+            }
+
+            let region = Rect::new(
+                x as f32 / width as f32,
+                y as f32 / height as f32,
+                image.width as f32 / width as f32,
+                image.height as f32 / height as f32,
+            );
+
+            regions.insert(image.name.clone(), region);
+        }
+
+        let texture = Texture::from_pixels(width, height, &pixels)?;
+
+        Ok(TextureAtlas { texture: Rc::new(texture), regions })
+    }
+}
+
+/// A texture made of many packed images.
+pub struct TextureAtlas {
+    /// The atlas texture.
+    pub texture: Rc<Texture>,
+
+    /// The region (in uv coordinates) of each packed image.
+    pub regions: HashMap<String, Rect>,
+}
+
+impl TextureAtlas {
+    /// Get the region (in uv coordinates) of a packed image.
+    /// # Arguments
+    /// * `name` - The name given to the image when it was added.
+    pub fn get_region(&self, name: &str) -> Option<Rect> {
+        self.regions.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An atlas builder with blank images of the given sizes, named by index.
+    fn builder(max_size: u32, padding: u32, sizes: &[(u32, u32)]) -> AtlasBuilder {
+        let mut builder = AtlasBuilder::new(max_size, padding);
+
+        for (i, (w, h)) in sizes.iter().enumerate() {
+            builder.add_pixels(&i.to_string(), *w, *h, vec![0; *w as usize * *h as usize * 4]).unwrap();
+        }
+
+        builder
+    }
+
+    /// Check that the packed images are inside the atlas and don't overlap, padding included.
+    fn check(builder: &AtlasBuilder, packing: &Packing) {
+        let (width, height) = packing.size;
+        assert!(width.is_power_of_two() && height.is_power_of_two());
+        assert!(width <= builder.max_size && height <= builder.max_size);
+
+        let rects = builder.images.iter().zip(packing.positions.iter())
+            .map(|(image, (x, y))| (*x, *y, image.width + builder.padding, image.height + builder.padding))
+            .collect::<Vec<(u32, u32, u32, u32)>>();
+
+        for (i, (x, y, w, h)) in rects.iter().enumerate() {
+            assert!(x + w - builder.padding <= width && y + h - builder.padding <= height, "image {} is outside the atlas", i);
+
+            for (j, (ox, oy, ow, oh)) in rects.iter().enumerate().skip(i + 1) {
+                let apart = x + w <= *ox || ox + ow <= *x || y + h <= *oy || oy + oh <= *y;
+                assert!(apart, "images {} and {} overlap", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn packs_without_overlap() {
+        let builder = builder(256, 2, &[(16, 16), (32, 8), (8, 40), (20, 20), (64, 4), (1, 1), (30, 12)]);
+        let packing = builder.pack().unwrap();
+
+        check(&builder, &packing);
+        assert_eq!(packing.size, (128, 64));
+    }
+
+    #[test]
+    fn fits_exactly() {
+        let builder = builder(64, 0, &[(32, 32); 4]);
+        let packing = builder.pack().unwrap();
+
+        check(&builder, &packing);
+        assert_eq!(packing.size, (64, 64));
+    }
+
+    #[test]
+    fn widens_when_too_tall() {
+        // Thin and tall images, a square atlas would need rows beyond the max size.
+        let builder = builder(128, 0, &[(10, 100); 10]);
+        let packing = builder.pack().unwrap();
+
+        check(&builder, &packing);
+        assert_eq!(packing.size, (128, 128));
+    }
+
+    #[test]
+    fn rejects_what_doesnt_fit() {
+        assert!(builder(64, 0, &[(65, 1)]).pack().is_err());
+        assert!(builder(64, 0, &[(32, 32); 5]).pack().is_err());
+        assert!(AtlasBuilder::new(64, 0).add_pixels("bad", 2, 2, vec![0; 15]).is_err());
+
+        // 65536 * 65536 * 4 bytes doesn't fit in an `u32`.
+        assert!(AtlasBuilder::new(64, 0).add_pixels("huge", 65536, 65536, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn empty_atlas() {
+        let packing = AtlasBuilder::new(64, 0).pack().unwrap();
+        assert_eq!(packing.size, (1, 1));
+        assert!(packing.positions.is_empty());
+    }
+
+    #[test]
+    fn large_area_doesnt_overflow() {
+        // 8 images of 32768x32768 have an area of 2^33 pixels, the sum overflows an u32.
+        let images = (0..8).map(|i| AtlasImage { name: i.to_string(), width: 32768, height: 32768, pixels: Vec::new() }).collect();
+        let builder = AtlasBuilder { images, max_size: 1 << 17, padding: 0 };
+        let packing = builder.pack().unwrap();
+
+        check(&builder, &packing);
+        assert_eq!(packing.size, (1 << 17, 1 << 16));
+    }
+}
@@ -102,7 +102,8 @@ impl<T: Any> ComponentArray<T> {
         Some(self.components[self.entity_to_component[*entity]].as_mut()?)
     }
 
-    pub fn get_entities(&self) -> &Vec<Entity> {
-        &self.entity_to_component
+    /// Get the entities that own a component, in storage order.
+    pub fn get_entities(&self) -> &[Entity] {
+        &self.component_to_entity[..self.components.len()]
     }
 }
\ No newline at end of file
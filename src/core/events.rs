@@ -0,0 +1,56 @@
+
+/// A queue of events of the same type.
+///
+/// Events are stored in a component of a dedicated entity and
+/// stay readable by every system until the queue is cleared.
+///
+/// Queues are not cleared at the end of the frame: the system that
+/// sends an event type clears it at the start of its own update. An
+/// event is then read by the systems registered after the sender in
+/// the same frame, and by the systems registered before it one frame
+/// later, right before it is cleared.
+pub struct Events<T> {
+    events: Vec<T>,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Events::new()
+    }
+}
+
+impl<T> Events<T> {
+    /// Create new empty `Events` queue.
+    pub fn new() -> Self {
+        Self {
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an event to the queue.
+    /// # Arguments
+    /// * `event` - The event to add.
+    pub fn send(&mut self, event: T) {
+        self.events.push(event);
+    }
+
+    /// All events in the queue, oldest first.
+    pub fn as_slice(&self) -> &[T] {
+        &self.events
+    }
+
+    /// Remove all events from the queue.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// The amount of events in the queue.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// `true` if there is no event in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, rc::Rc};
+
+use cgmath::{
+    Vector2,
+    Vector4,
+};
+
+use crate::core::gl::shader::{Shader, ShaderError};
+
+/// A vertex of the `BuiltinShader::Line` shader.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct LineVertex {
+    /// The vertex position.
+    pub pos:    Vector2<f32>,
+
+    /// The vertex color.
+    pub color:  Vector4<f32>,
+}
+
+impl LineVertex {
+    /// Create new `LineVertex`
+    /// # Arguments
+    /// * `pos` - The vertex position.
+    /// * `color` - The vertex color.
+    pub fn new(pos: Vector2<f32>, color: Vector4<f32>) -> Self {
+        Self {
+            pos,
+            color,
+        }
+    }
+}
+
+crate::impl_vertex!(LineVertex { pos => 0, color => 1 });
+
+/// The shaders shipped with the library, embedded in the binary.
+///
+/// Every shader takes a `view_projection` (`mat4`) uniform, the mesh
+/// shaders also take a `model` (`mat4`) uniform.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BuiltinShader {
+    /// A `Mesh2D` filled with the `color` (`vec4`) uniform.
+    UnlitColor,
+
+    /// A mesh colored by its vertices: position (location 0) and color (`vec4`, location 2).
+    VertexColor,
+
+    /// A `Mesh2D` textured with the `main_texture` sampler, multiplied by the `tint` (`vec4`) uniform.
+    Textured,
+
+    /// The `SpriteBatch` shader: position, uv and color, textured with the `sprite_texture` sampler.
+    Sprite,
+
+    /// Like `Sprite`, but `sprite_texture` is a glyph atlas with the coverage in the red channel.
+    Text,
+
+    /// Colored lines of `LineVertex`: position (location 0) and color (`vec4`, location 1), no `model` uniform.
+    Line,
+}
+
+thread_local! {
+    /// The built-in shaders already compiled, shared by the whole render thread.
+    static COMPILED: std::cell::RefCell<HashMap<BuiltinShader, Rc<Shader>>> = std::cell::RefCell::new(HashMap::new());
+}
+
+impl BuiltinShader {
+    /// All the built-in shaders.
+    pub const ALL: [BuiltinShader; 6] = [
+        BuiltinShader::UnlitColor,
+        BuiltinShader::VertexColor,
+        BuiltinShader::Textured,
+        BuiltinShader::Sprite,
+        BuiltinShader::Text,
+        BuiltinShader::Line,
+    ];
+
+    /// The vertex and fragment shader sources.
+    pub fn sources(self) -> (&'static str, &'static str) {
+        match self {
+            BuiltinShader::UnlitColor => (
+                include_str!("../../../shaders/builtin/mesh.vert.glsl"),
+                include_str!("../../../shaders/builtin/unlit_color.frag.glsl"),
+            ),
+            BuiltinShader::VertexColor => (
+                include_str!("../../../shaders/builtin/vertex_color.vert.glsl"),
+                include_str!("../../../shaders/builtin/vertex_color.frag.glsl"),
+            ),
+            BuiltinShader::Textured => (
+                include_str!("../../../shaders/builtin/mesh.vert.glsl"),
+                include_str!("../../../shaders/builtin/textured.frag.glsl"),
+            ),
+            BuiltinShader::Sprite => (
+                include_str!("../../../shaders/builtin/sprite.vert.glsl"),
+                include_str!("../../../shaders/builtin/sprite.frag.glsl"),
+            ),
+            BuiltinShader::Text => (
+                include_str!("../../../shaders/builtin/sprite.vert.glsl"),
+                include_str!("../../../shaders/builtin/text.frag.glsl"),
+            ),
+            BuiltinShader::Line => (
+                include_str!("../../../shaders/builtin/line.vert.glsl"),
+                include_str!("../../../shaders/builtin/line.frag.glsl"),
+            ),
+        }
+    }
+
+    /// Compile a new program of the shader.
+    pub fn compile(self) -> Result<Shader, ShaderError> {
+        let (vertex, fragment) = self.sources();
+        Shader::from_source(vertex, fragment)
+    }
+
+    /// Get the shader, compiled the first time and shared afterwards.
+    ///
+    /// Needs a current GL context.
+    pub fn get(self) -> Result<Rc<Shader>, ShaderError> {
+        if let Some(shader) = COMPILED.with(|c| c.borrow().get(&self).cloned()) {
+            return Ok(shader)
+        }
+
+        let shader = Rc::new(self.compile()?);
+        COMPILED.with(|c| c.borrow_mut().insert(self, shader.clone()));
+        Ok(shader)
+    }
+}
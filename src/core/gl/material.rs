@@ -0,0 +1,196 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+use crate::core::gl::{
+    shader::{Shader, ShaderError},
+    texture::Texture,
+    uniform::UniformType,
+};
+
+/// How the drawn pixels are blended with the pixels already drawn.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    /// No blending, the pixels are replaced.
+    Opaque,
+
+    /// Classic transparency (`src * a + dst * (1 - a)`).
+    Alpha,
+
+    /// The colors are added (`src * a + dst`).
+    Additive,
+
+    /// The colors are multiplied (`src * dst`).
+    Multiply,
+}
+
+impl BlendMode {
+    /// Apply the blend state.
+    pub fn apply(self) {
+        unsafe {
+            match self {
+                BlendMode::Opaque => gl::Disable(gl::BLEND),
+                BlendMode::Alpha => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+                },
+                BlendMode::Additive => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE);
+                },
+                BlendMode::Multiply => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::DST_COLOR, gl::ZERO);
+                },
+            }
+        }
+    }
+}
+
+/// An uniform value of any type, stored by a `Material`.
+trait MaterialUniform {
+    /// Upload the value to the shader in use.
+    fn apply(&self, shader: &Shader, name: &str) -> Result<(), ShaderError>;
+}
+
+impl<T: UniformType> MaterialUniform for Vec<T> {
+    fn apply(&self, shader: &Shader, name: &str) -> Result<(), ShaderError> {
+        // Checked again, the shader may have been reloaded since the value was set.
+        shader.set_uniform_array(name, self)
+    }
+}
+
+/// How a mesh looks: a shader with its uniform values, textures and blend state.
+///
+/// Materials are shared between meshes with an `Rc`, so a thousand
+/// meshes that look the same use a single shader program. `RenderSys`
+/// applies the material before drawing, meshes are drawn sorted by
+/// material to minimise the state changes.
+pub struct Material {
+    /// The shader used to draw.
+    shader: Rc<Shader>,
+
+    /// The uniform values, uploaded when the material is applied.
+    uniforms: RefCell<HashMap<String, Box<dyn MaterialUniform>>>,
+
+    /// The textures and the name of their sampler, bound to the texture unit of their index.
+    textures: Vec<(String, Rc<Texture>)>,
+
+    /// How the drawn pixels are blended.
+    blend: BlendMode,
+}
+
+impl Material {
+    /// Create new opaque `Material` without uniform values and textures.
+    /// # Arguments
+    /// * `shader` - The shader used to draw.
+    pub fn new(shader: Rc<Shader>) -> Self {
+        Self {
+            shader,
+            uniforms: RefCell::new(HashMap::new()),
+            textures: Vec::new(),
+            blend: BlendMode::Opaque,
+        }
+    }
+
+    /// Bind a texture to a sampler of the shader.
+    /// # Arguments
+    /// * `sampler` - The name of the sampler uniform.
+    /// * `texture` - The texture.
+    pub fn with_texture(mut self, sampler: &str, texture: Rc<Texture>) -> Self {
+        self.textures.push((String::from(sampler), texture));
+        self
+    }
+
+    /// Set how the drawn pixels are blended.
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// The shader used to draw.
+    pub fn get_shader(&self) -> &Rc<Shader> {
+        &self.shader
+    }
+
+    /// How the drawn pixels are blended.
+    pub fn get_blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    /// Set an uniform value, uploaded each time the material is applied.
+    ///
+    /// Fails if the shader has no active uniform with this name or if
+    /// the value type doesn't match the uniform type.
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader) to set.
+    /// * `value` - The new value of the uniform variable.
+    pub fn set_uniform<T: UniformType + 'static>(&self, name: &str, value: T) -> Result<(), ShaderError> {
+        self.set_uniform_array(name, &[value])
+    }
+
+    /// Set the values of an uniform array, uploaded each time the material is applied.
+    /// # Arguments
+    /// * `name` - The name of the uniform array (in the shader) to set.
+    /// * `values` - The new values of the uniform array.
+    pub fn set_uniform_array<T: UniformType + 'static>(&self, name: &str, values: &[T]) -> Result<(), ShaderError> {
+        self.shader.check_uniform::<T>(name, values.len())?;
+
+        self.uniforms.borrow_mut().insert(String::from(name), Box::new(values.to_vec()));
+        Ok(())
+    }
+
+    /// Use the shader, then apply the blend state, the textures and the uniform values.
+    ///
+    /// Everything that matches the shader is applied, the first sampler or uniform
+    /// that doesn't (after a shader reload, ...) is returned as an error.
+    pub fn apply(&self) -> Result<(), ShaderError> {
+        self.shader.use_it();
+        self.blend.apply();
+
+        let mut result = Ok(());
+
+        for (unit, (sampler, texture)) in self.textures.iter().enumerate() {
+            texture.bind(unit as u32);
+            result = result.and(self.shader.set_uniform(sampler, unit as i32));
+        }
+
+        for (name, value) in self.uniforms.borrow().iter() {
+            result = result.and(value.apply(&self.shader, name));
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::gl::uniform::UniformInfo;
+    use cgmath::Vector2;
+
+    fn material() -> Material {
+        let uniforms = vec![
+            UniformInfo { name: String::from("time"), gl_type: gl::FLOAT, size: 1, location: 0 },
+            UniformInfo { name: String::from("offsets"), gl_type: gl::FLOAT_VEC2, size: 4, location: 1 },
+        ];
+
+        Material::new(Rc::new(Shader::headless(uniforms, Vec::new())))
+    }
+
+    #[test]
+    fn uniform_values_are_checked_against_the_shader() {
+        let material = material();
+
+        assert!(material.set_uniform("time", 1.0f32).is_ok());
+        assert!(material.set_uniform_array("offsets", &[Vector2::new(0.0f32, 1.0); 4]).is_ok());
+
+        assert!(matches!(material.set_uniform("missing", 1.0f32), Err(ShaderError::Uniform(_))));
+        assert!(matches!(material.set_uniform("time", 1i32), Err(ShaderError::Uniform(_))));
+        assert!(matches!(material.set_uniform_array("offsets", &[Vector2::new(0.0f32, 1.0); 5]), Err(ShaderError::Uniform(_))));
+
+        assert_eq!(material.uniforms.borrow().len(), 2);
+    }
+}
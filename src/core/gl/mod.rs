@@ -1 +1,10 @@
-pub mod shader;
\ No newline at end of file
+pub mod object;
+pub mod preprocessor;
+pub mod shader;
+pub mod builtin;
+pub mod uniform;
+pub mod vertex;
+pub mod material;
+pub mod texture;
+pub mod storage_buffer;
+pub mod sprite_batch;
\ No newline at end of file
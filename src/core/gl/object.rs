@@ -0,0 +1,382 @@
+use gl::types::GLuint;
+use std::{
+    cell::Cell,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+/// The types of GL objects owned by a `GlObject`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GlObjectKind {
+    Program,
+    Buffer,
+    VertexArray,
+    Texture,
+}
+
+impl GlObjectKind {
+    /// All the kinds, in the order of the live counters.
+    pub const ALL: [GlObjectKind; 4] = [
+        GlObjectKind::Program,
+        GlObjectKind::Buffer,
+        GlObjectKind::VertexArray,
+        GlObjectKind::Texture,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The amount of live GL objects of each kind.
+static LIVE_OBJECTS: [AtomicUsize; 4] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+/// The objects waiting to be deleted, with the context of the vertex arrays.
+static PENDING_DELETIONS: Mutex<Vec<(GlObjectKind, GLuint, usize)>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// `true` on the thread that owns the GL context.
+    static IS_RENDER_THREAD: Cell<bool> = const { Cell::new(false) };
+
+    /// The context current on the render thread, `0` for none.
+    static CURRENT_CONTEXT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Mark the current thread as the render thread.
+///
+/// GL objects dropped on the render thread are deleted right away,
+/// the others are deleted by the next call to `delete_pending`.
+/// Called by `WindowSys` once the GL methods are loaded.
+pub fn mark_render_thread() {
+    IS_RENDER_THREAD.with(|r| r.set(true));
+}
+
+/// `true` if the current thread is the render thread.
+pub fn is_render_thread() -> bool {
+    IS_RENDER_THREAD.with(|r| r.get())
+}
+
+/// Tell which context is current on the render thread.
+///
+/// Called by `Window::make_current`, vertex arrays remember it.
+/// # Arguments
+/// * `context` - The context id, `0` for none.
+pub fn set_current_context(context: usize) {
+    CURRENT_CONTEXT.with(|c| c.set(context));
+}
+
+/// The context current on the render thread, `0` for none.
+pub fn current_context() -> usize {
+    CURRENT_CONTEXT.with(|c| c.get())
+}
+
+/// Delete the GL objects dropped outside of the render thread,
+/// and the vertex arrays dropped while another context was current.
+///
+/// Does nothing outside of the render thread. The vertex arrays
+/// of the other contexts stay pending. Called by `RenderSys`
+/// once per frame for each window.
+pub fn delete_pending() {
+    if !is_render_thread() {
+        return
+    }
+
+    let context = current_context();
+    let pending = {
+        let mut pending = PENDING_DELETIONS.lock().unwrap();
+        let (now, later) = pending.drain(..).partition(|(_, _, c)| *c == 0 || *c == context);
+        *pending = later;
+        now
+    };
+
+    for (kind, id, _) in pending {
+        unsafe { delete(kind, id) };
+    }
+}
+
+/// The amount of GL objects (of every kind) not deleted yet.
+pub fn live_objects() -> usize {
+    LIVE_OBJECTS.iter().map(|c| c.load(Ordering::SeqCst)).sum()
+}
+
+/// The amount of GL objects of a certain kind not deleted yet.
+/// # Arguments
+/// * `kind` - The kind of GL object.
+pub fn live_objects_of(kind: GlObjectKind) -> usize {
+    LIVE_OBJECTS[kind.index()].load(Ordering::SeqCst)
+}
+
+/// Delete a GL object right now.
+///
+/// The GL methods may not be loaded when there is no context
+/// (headless worlds), in which case nothing can be deleted.
+unsafe fn delete(kind: GlObjectKind, id: GLuint) {
+    match kind {
+        GlObjectKind::Program if gl::DeleteProgram::is_loaded() => gl::DeleteProgram(id),
+        GlObjectKind::Buffer if gl::DeleteBuffers::is_loaded() => gl::DeleteBuffers(1, &id),
+        GlObjectKind::VertexArray if gl::DeleteVertexArrays::is_loaded() => gl::DeleteVertexArrays(1, &id),
+        GlObjectKind::Texture if gl::DeleteTextures::is_loaded() => gl::DeleteTextures(1, &id),
+        _ => {},
+    }
+
+    LIVE_OBJECTS[kind.index()].fetch_sub(1, Ordering::SeqCst);
+}
+
+/// Owns a GL object and deletes it when dropped.
+///
+/// Vertex arrays are not shared between contexts, they belong to the
+/// context current when they are created. One dropped while another
+/// context is current is deleted by `delete_pending` once its context
+/// is current again.
+pub struct GlObject {
+    kind: GlObjectKind,
+    id: GLuint,
+
+    /// The context of a vertex array, `0` for the shared objects.
+    context: usize,
+}
+
+impl GlObject {
+    /// Take the ownership of an existing GL object.
+    /// # Arguments
+    /// * `kind` - The kind of GL object.
+    /// * `id` - The GL object id, `0` for no object.
+    pub fn new(kind: GlObjectKind, id: GLuint) -> Self {
+        if id != 0 {
+            LIVE_OBJECTS[kind.index()].fetch_add(1, Ordering::SeqCst);
+        }
+
+        let context = if kind == GlObjectKind::VertexArray && id != 0 { current_context() } else { 0 };
+
+        Self {
+            kind,
+            id,
+            context,
+        }
+    }
+
+    /// Create a new GL object.
+    /// # Arguments
+    /// * `kind` - The kind of GL object.
+    pub fn generate(kind: GlObjectKind) -> Self {
+        let mut id = 0;
+
+        unsafe {
+            match kind {
+                GlObjectKind::Program     => id = gl::CreateProgram(),
+                GlObjectKind::Buffer      => gl::GenBuffers(1, &mut id),
+                GlObjectKind::VertexArray => gl::GenVertexArrays(1, &mut id),
+                GlObjectKind::Texture     => gl::GenTextures(1, &mut id),
+            }
+        }
+
+        GlObject::new(kind, id)
+    }
+
+    /// An empty handle that owns no GL object.
+    /// # Arguments
+    /// * `kind` - The kind of GL object.
+    pub fn null(kind: GlObjectKind) -> Self {
+        GlObject::new(kind, 0)
+    }
+
+    /// Just return the GL object id.
+    pub fn get_id(&self) -> GLuint {
+        self.id
+    }
+
+    /// The kind of GL object.
+    pub fn get_kind(&self) -> GlObjectKind {
+        self.kind
+    }
+
+    /// The context of a vertex array, `0` for the shared objects.
+    pub fn get_context(&self) -> usize {
+        self.context
+    }
+
+    /// `true` if the handle owns no GL object.
+    pub fn is_null(&self) -> bool {
+        self.id == 0
+    }
+}
+
+impl Drop for GlObject {
+    fn drop(&mut self) {
+        if self.id == 0 {
+            return
+        }
+
+        if is_render_thread() && (self.context == 0 || self.context == current_context()) {
+            unsafe { delete(self.kind, self.id) };
+        } else {
+            PENDING_DELETIONS.lock().unwrap().push((self.kind, self.id, self.context));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The counters and the pending deletions are global, the tests run one at a time.
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    /// Objects are created with fake ids, the GL methods are not loaded so
+    /// `delete` only updates the counters.
+    fn fake(kind: GlObjectKind, id: GLuint) -> GlObject {
+        GlObject::new(kind, id)
+    }
+
+    fn pending() -> Vec<(GlObjectKind, GLuint)> {
+        PENDING_DELETIONS.lock().unwrap().iter().map(|(kind, id, _)| (*kind, *id)).collect()
+    }
+
+    #[test]
+    fn null_objects_are_not_counted() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let live = live_objects();
+
+        let object = GlObject::null(GlObjectKind::Texture);
+        assert!(object.is_null());
+        assert_eq!(live_objects(), live);
+
+        drop(object);
+        assert_eq!(live_objects(), live);
+        assert!(pending().is_empty());
+    }
+
+    #[test]
+    fn drops_outside_of_the_render_thread_are_deferred() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let live = live_objects_of(GlObjectKind::Buffer);
+
+        // The test thread is not the render thread yet.
+        let object = fake(GlObjectKind::Buffer, 41);
+        assert_eq!(live_objects_of(GlObjectKind::Buffer), live + 1);
+
+        drop(object);
+        assert_eq!(pending(), vec![(GlObjectKind::Buffer, 41)]);
+        assert_eq!(live_objects_of(GlObjectKind::Buffer), live + 1);
+
+        // Only the render thread deletes the pending objects.
+        delete_pending();
+        assert_eq!(pending().len(), 1);
+
+        mark_render_thread();
+        delete_pending();
+        assert!(pending().is_empty());
+        assert_eq!(live_objects_of(GlObjectKind::Buffer), live);
+    }
+
+    #[test]
+    fn objects_dropped_on_another_thread_are_deleted_by_the_render_thread() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        mark_render_thread();
+
+        let live = live_objects();
+        let objects = vec![fake(GlObjectKind::Texture, 7), fake(GlObjectKind::VertexArray, 8)];
+        assert_eq!(live_objects(), live + 2);
+
+        std::thread::spawn(move || {
+            assert!(!is_render_thread());
+            drop(objects);
+        }).join().unwrap();
+
+        assert_eq!(pending(), vec![(GlObjectKind::Texture, 7), (GlObjectKind::VertexArray, 8)]);
+        assert_eq!(live_objects(), live + 2);
+
+        delete_pending();
+        assert!(pending().is_empty());
+        assert_eq!(live_objects(), live);
+    }
+
+    #[test]
+    fn drops_on_the_render_thread_are_immediate() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        mark_render_thread();
+
+        let live = live_objects_of(GlObjectKind::Program);
+        let object = fake(GlObjectKind::Program, 3);
+        assert_eq!(object.get_id(), 3);
+        assert_eq!(object.get_kind(), GlObjectKind::Program);
+        assert_eq!(live_objects_of(GlObjectKind::Program), live + 1);
+
+        drop(object);
+        assert!(pending().is_empty());
+        assert_eq!(live_objects_of(GlObjectKind::Program), live);
+    }
+
+    #[test]
+    fn vertex_arrays_are_deleted_in_their_context() {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        mark_render_thread();
+
+        let live = live_objects_of(GlObjectKind::VertexArray);
+
+        set_current_context(1);
+        let vao = fake(GlObjectKind::VertexArray, 5);
+        let buffer = fake(GlObjectKind::Buffer, 6);
+        assert_eq!(vao.get_context(), 1);
+        assert_eq!(buffer.get_context(), 0);
+
+        // Buffers are shared, vertex arrays wait for their context.
+        set_current_context(2);
+        drop((vao, buffer));
+        assert_eq!(pending(), vec![(GlObjectKind::VertexArray, 5)]);
+
+        delete_pending();
+        assert_eq!(pending().len(), 1);
+
+        set_current_context(1);
+        delete_pending();
+        assert!(pending().is_empty());
+        assert_eq!(live_objects_of(GlObjectKind::VertexArray), live);
+
+        set_current_context(0);
+    }
+
+    #[test]
+    fn destroyed_meshes_give_their_objects_back() {
+        use std::rc::Rc;
+        use crate::components::mesh_2d::{Mesh2D, Topology, Vertex2D};
+        use crate::core::{gl::{material::Material, shader::Shader}, world::World};
+
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        mark_render_thread();
+
+        let live = live_objects();
+        let material = Rc::new(Material::new(Rc::new(Shader::headless(Vec::new(), Vec::new()))));
+        let mesh = || {
+            let mut mesh = Mesh2D::empty::<Vertex2D>(Topology::Triangles, material.clone(), false).unwrap();
+            mesh.vbo = fake(GlObjectKind::Buffer, 11);
+            mesh.ebo = fake(GlObjectKind::Buffer, 12);
+            mesh
+        };
+
+        // A mesh entity destroyed on the render thread.
+        let mut world = World::new(16, 16, 16, 1);
+        let ecm = &mut world.entity_component_manager;
+        let entity = ecm.create_entity().unwrap();
+        ecm.add_component(&entity, mesh());
+        assert_eq!(live_objects(), live + 2);
+
+        ecm.destroy_entity(&entity);
+        assert_eq!(live_objects(), live);
+
+        // A mesh dropped without entity.
+        let mesh = mesh();
+        assert_eq!(live_objects(), live + 2);
+
+        drop(mesh);
+        assert_eq!(live_objects(), live);
+        assert!(pending().is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::gl::shader::{ShaderDiagnostic, ShaderError};
+
+/// The `#version` line prepended to the shaders that don't have one.
+pub const DEFAULT_VERSION: &str = "410 core";
+
+/// A shader source ready to be compiled, with the origin
+/// (file and line) of each of its lines.
+pub struct PreprocessedSource {
+    /// The shader source.
+    pub code: String,
+
+    /// The files read to build the source, the main file first.
+    pub files: Vec<PathBuf>,
+
+    /// The file index and line of each line of `code`,
+    /// `None` for the injected lines (`#version`, `#define`).
+    origins: Vec<Option<(usize, u32)>>,
+}
+
+impl PreprocessedSource {
+    /// Get the file and line a line of the preprocessed source comes from.
+    ///
+    /// # Arguments
+    /// * `line` - The line in the preprocessed source, starting from 1.
+    pub fn origin(&self, line: u32) -> Option<(&Path, u32)> {
+        let (file, line) = (*self.origins.get((line as usize).checked_sub(1)?)?)?;
+        Some((&self.files[file], line))
+    }
+
+    /// Replace the line numbers of a compiler log (`0(12)` or `0:12`)
+    /// by the file and line they come from (`common.glsl:3`).
+    ///
+    /// # Arguments
+    /// * `log` - The compiler log.
+    pub fn map_log(&self, log: &str) -> String {
+        log.lines().map(|line| {
+            match find_line_ref(line) {
+                Some((start, end, n)) => match self.origin(n) {
+                    Some((file, n)) => format!("{}{}:{}{}", &line[..start], file.display(), n, &line[end..]),
+                    None => String::from(line),
+                },
+                None => String::from(line),
+            }
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Make a compilation error refer to the original files and lines.
+    ///
+    /// # Arguments
+    /// * `error` - The error returned when compiling `code`.
+    pub fn map_error(&self, error: ShaderError) -> ShaderError {
+        match error {
+            ShaderError::Compile { stage, log, diagnostics } => ShaderError::Compile {
+                stage,
+                log: self.map_log(&log),
+                diagnostics: diagnostics.into_iter().map(|d| {
+                    match d.line.and_then(|n| self.origin(n)) {
+                        Some((file, line)) => ShaderDiagnostic {
+                            file: Some(file.to_path_buf()),
+                            line: Some(line),
+                            message: d.message,
+                        },
+                        None => d,
+                    }
+                }).collect(),
+            },
+            error => error,
+        }
+    }
+}
+
+/// Find the first line reference of a compiler log line.
+///
+/// GLSL compilers write `<source>(<line>)` (NVIDIA) or `<source>:<line>` (Mesa, AMD).
+/// Returns the reference position and the line.
+pub fn find_line_ref(text: &str) -> Option<(usize, usize, u32)> {
+    let bytes = text.as_bytes();
+
+    for start in 0..bytes.len() {
+        if start > 0 && !bytes[start - 1].is_ascii_whitespace() {
+            continue
+        }
+
+        let mut i = start;
+        while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1 }
+
+        if i == start || i >= bytes.len() || (bytes[i] != b'(' && bytes[i] != b':') {
+            continue
+        }
+
+        let mut j = i + 1;
+        while j < bytes.len() && bytes[j].is_ascii_digit() { j += 1 }
+
+        if j == i + 1 {
+            continue
+        }
+
+        let end = match bytes[i] {
+            b'(' if j < bytes.len() && bytes[j] == b')' => j + 1,
+            b'(' => continue,
+            _ => j,
+        };
+
+        return Some((start, end, text[i + 1..j].parse().ok()?))
+    }
+
+    None
+}
+
+/// Prepare shader sources before compilation.
+///
+/// - `#include "file.glsl"` lines are replaced by the file content,
+///   the path is relative to the including file.
+/// - The `#version` line of the source (or `DEFAULT_VERSION`) is moved on top.
+/// - The defines are injected right after the `#version` line.
+#[derive(Clone)]
+pub struct Preprocessor {
+    /// The version used when the source has no `#version` line.
+    version: String,
+
+    /// The injected defines, by name.
+    defines: Vec<(String, String)>,
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Preprocessor::new()
+    }
+}
+
+impl Preprocessor {
+    /// Create new `Preprocessor` without defines.
+    pub fn new() -> Self {
+        Self {
+            version: String::from(DEFAULT_VERSION),
+            defines: Vec::new(),
+        }
+    }
+
+    /// Set the version used when the source has no `#version` line.
+    /// # Arguments
+    /// * `version` - The version, like `"410 core"`.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = String::from(version);
+        self
+    }
+
+    /// Inject a define (`#define <name> <value>`), replacing a previous define with the same name.
+    /// # Arguments
+    /// * `name` - The define name.
+    /// * `value` - The define value, can be empty.
+    pub fn with_define(mut self, name: &str, value: &str) -> Self {
+        self.defines.retain(|(n, _)| n != name);
+        self.defines.push((String::from(name), String::from(value)));
+        self
+    }
+
+    /// The injected defines.
+    pub fn get_defines(&self) -> &[(String, String)] {
+        &self.defines
+    }
+
+    /// Preprocess a shader file.
+    /// # Arguments
+    /// * `path` - The path of the shader.
+    pub fn process_file(&self, path: &str) -> Result<PreprocessedSource, ShaderError> {
+        let src = read_source(Path::new(path))?;
+        self.process(&src, PathBuf::from(path))
+    }
+
+    /// Preprocess a shader source.
+    /// # Arguments
+    /// * `src` - The shader source.
+    /// * `name` - The name of the source in error messages, includes are relative to its directory.
+    pub fn process_source(&self, src: &str, name: &str) -> Result<PreprocessedSource, ShaderError> {
+        self.process(src, PathBuf::from(name))
+    }
+
+    fn process(&self, src: &str, path: PathBuf) -> Result<PreprocessedSource, ShaderError> {
+        let mut out = PreprocessedSource {
+            code: String::new(),
+            files: vec![path],
+            origins: Vec::new(),
+        };
+
+        let version = src.lines()
+            .map(str::trim)
+            .find_map(|l| l.strip_prefix("#version"))
+            .map(str::trim)
+            .unwrap_or(&self.version)
+            .to_string();
+
+        push_line(&mut out, &format!("#version {}", version), None);
+
+        for (name, value) in self.defines.iter() {
+            push_line(&mut out, &format!("#define {} {}", name, value), None);
+        }
+
+        let mut stack = Vec::new();
+        include(&mut out, src, 0, &mut stack)?;
+
+        Ok(out)
+    }
+}
+
+/// Read a source file.
+fn read_source(path: &Path) -> Result<String, ShaderError> {
+    let bytes = std::fs::read(path).map_err(|error| ShaderError::Io { path: path.to_path_buf(), error })?;
+    String::from_utf8(bytes).map_err(|_| ShaderError::InvalidUtf8 { path: path.to_path_buf() })
+}
+
+fn push_line(out: &mut PreprocessedSource, line: &str, origin: Option<(usize, u32)>) {
+    out.code.push_str(line);
+    out.code.push('\n');
+    out.origins.push(origin);
+}
+
+/// Remove the `.` and `..` components of a path, `sub/../a.glsl` becomes `a.glsl`.
+///
+/// Purely lexical, the leading `..` of relative paths are kept.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => { out.pop(); },
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {},
+                _ => out.push(".."),
+            },
+            component => out.push(component),
+        }
+    }
+
+    out
+}
+
+/// What identifies a source file when looking for include cycles:
+/// its canonical path (symbolic links resolved) if it exists.
+fn identity(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| normalize(path))
+}
+
+/// The `file:line` of a source line, for error messages.
+fn location(out: &PreprocessedSource, file: usize, index: usize) -> String {
+    format!("{}:{}", out.files[file].display(), index + 1)
+}
+
+/// Append a source to the output, resolving its includes.
+///
+/// # Arguments
+/// * `file` - The index of the source file in `out.files`.
+/// * `stack` - The identity of the files being included, to detect include cycles.
+fn include(out: &mut PreprocessedSource, src: &str, file: usize, stack: &mut Vec<PathBuf>) -> Result<(), ShaderError> {
+    stack.push(identity(&out.files[file]));
+
+    for (i, line) in src.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("#version") {
+            continue
+        }
+
+        let name = match trimmed.strip_prefix("#include") {
+            Some(name) => name.trim(),
+            None => {
+                push_line(out, line, Some((file, i as u32 + 1)));
+                continue
+            },
+        };
+
+        if name.len() < 2 || !name.starts_with('"') || !name.ends_with('"') {
+            return Err(ShaderError::Include(format!("{}: expected #include \"file\"", location(out, file, i))))
+        }
+
+        let dir = out.files[file].parent().map(Path::to_path_buf).unwrap_or_default();
+        let path = normalize(&dir.join(&name[1..name.len() - 1]));
+
+        if stack.contains(&identity(&path)) {
+            return Err(ShaderError::Include(format!("{}: '{}' includes itself", location(out, file, i), path.display())))
+        }
+
+        let included = read_source(&path).map_err(|error| ShaderError::IncludeFile {
+            location: location(out, file, i),
+            error: Box::new(error),
+        })?;
+
+        out.files.push(path);
+        let index = out.files.len() - 1;
+        include(out, &included, index, stack)?;
+    }
+
+    stack.pop();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::temp_dir::TempDir;
+
+    /// A directory of shader sources.
+    fn sources(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new(&format!("preprocessor-{}", name));
+
+        for (path, src) in files {
+            dir.write(path, src);
+        }
+
+        dir
+    }
+
+    #[test]
+    fn resolves_includes_relative_to_the_including_file() {
+        let dir = sources("include", &[
+            ("main.glsl", "#include \"lib/light.glsl\"\nvoid main() {}"),
+            ("lib/light.glsl", "#include \"../common.glsl\"\nfloat light;"),
+            ("common.glsl", "float common;"),
+        ]);
+
+        let out = Preprocessor::new().process_file(dir.path("main.glsl").to_str().unwrap()).unwrap();
+
+        assert_eq!(out.code, "#version 410 core\nfloat common;\nfloat light;\nvoid main() {}\n");
+        assert_eq!(out.files.len(), 3);
+        assert_eq!(out.files[2], normalize(&dir.path("common.glsl")));
+    }
+
+    #[test]
+    fn rejects_include_cycles() {
+        let dir = sources("cycle", &[
+            ("a.glsl", "#include \"sub/b.glsl\""),
+            ("sub/b.glsl", "#include \"../sub/../a.glsl\""),
+        ]);
+
+        match Preprocessor::new().process_file(dir.path("a.glsl").to_str().unwrap()) {
+            Err(ShaderError::Include(msg)) => assert!(msg.contains("includes itself"), "{}", msg),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("the include cycle was not detected"),
+        }
+    }
+
+    #[test]
+    fn includes_the_same_file_twice_without_cycle() {
+        let dir = sources("twice", &[
+            ("main.glsl", "#include \"common.glsl\"\n#include \"./common.glsl\""),
+            ("common.glsl", "float x;"),
+        ]);
+
+        let out = Preprocessor::new().process_file(dir.path("main.glsl").to_str().unwrap()).unwrap();
+        assert_eq!(out.code, "#version 410 core\nfloat x;\nfloat x;\n");
+    }
+
+    #[test]
+    fn rejects_malformed_includes() {
+        let result = Preprocessor::new().process_source("#include <common.glsl>", "<test>");
+        assert!(matches!(result, Err(ShaderError::Include(_))));
+    }
+
+    #[test]
+    fn hoists_the_version_line() {
+        let src = "// header\n#version 430 core\nvoid main() {}";
+        let out = Preprocessor::new().with_version("330 core").process_source(src, "<test>").unwrap();
+
+        assert_eq!(out.code, "#version 430 core\n// header\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn uses_the_default_version() {
+        let out = Preprocessor::new().with_version("450 core").process_source("void main() {}", "<test>").unwrap();
+        assert_eq!(out.code, "#version 450 core\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn injects_defines_after_the_version() {
+        let out = Preprocessor::new()
+            .with_define("LIGHTING", "1")
+            .with_define("SHADOWS", "")
+            .with_define("LIGHTING", "2")
+            .process_source("#version 410 core\nvoid main() {}", "<test>")
+            .unwrap();
+
+        assert_eq!(out.code, "#version 410 core\n#define SHADOWS \n#define LIGHTING 2\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn maps_lines_to_their_origin() {
+        let dir = sources("origin", &[
+            ("main.glsl", "#version 410 core\n#include \"common.glsl\"\nvoid main() {}"),
+            ("common.glsl", "float a;\nfloat b;"),
+        ]);
+
+        let out = Preprocessor::new().with_define("X", "1").process_file(dir.path("main.glsl").to_str().unwrap()).unwrap();
+
+        // #version, #define, float a, float b, void main
+        assert_eq!(out.origin(1), None);
+        assert_eq!(out.origin(2), None);
+        assert_eq!(out.origin(3), Some((out.files[1].as_path(), 1)));
+        assert_eq!(out.origin(4), Some((out.files[1].as_path(), 2)));
+        assert_eq!(out.origin(5), Some((out.files[0].as_path(), 3)));
+        assert_eq!(out.origin(0), None);
+        assert_eq!(out.origin(6), None);
+    }
+
+    #[test]
+    fn finds_nvidia_and_mesa_line_references() {
+        assert_eq!(find_line_ref("0(12) : error C0000: syntax error"), Some((0, 5, 12)));
+        assert_eq!(find_line_ref("ERROR: 0:12: 'x' : undeclared identifier"), Some((7, 11, 12)));
+        assert_eq!(find_line_ref("error: something went wrong"), None);
+        assert_eq!(find_line_ref("vec4(1.0)"), None);
+    }
+
+    #[test]
+    fn maps_compiler_logs() {
+        let out = Preprocessor::new().process_source("float a;\nfloat b;", "shader.glsl").unwrap();
+
+        assert_eq!(out.map_log("0(3) : error C1008: undefined variable"), "shader.glsl:2 : error C1008: undefined variable");
+        assert_eq!(out.map_log("ERROR: 0:2: 'a' : redefinition"), "ERROR: shader.glsl:1: 'a' : redefinition");
+
+        // The injected lines and the unknown lines are left as they are.
+        assert_eq!(out.map_log("0(1) : error"), "0(1) : error");
+        assert_eq!(out.map_log("0(9) : error"), "0(9) : error");
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize(Path::new("a/sub/../b/./c.glsl")), PathBuf::from("a/b/c.glsl"));
+        assert_eq!(normalize(Path::new("../a/../../b.glsl")), PathBuf::from("../../b.glsl"));
+        assert_eq!(normalize(Path::new("/../a.glsl")), PathBuf::from("/a.glsl"));
+    }
+}
@@ -1,54 +1,470 @@
 use cgmath::{ Vector1, Vector2, Vector3, Matrix4, prelude::* };
-use gl::types::{ GLuint, GLchar, GLint, GLboolean };
-use std::ffi::CString;
+use gl::types::{ GLuint, GLchar, GLint, GLboolean, GLenum, GLsizei, GLbitfield };
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    ffi::CString,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use crate::core::gl::{
+    object::{GlObject, GlObjectKind},
+    preprocessor::{self, PreprocessedSource, Preprocessor},
+    uniform::{self, UniformInfo, UniformType},
+    vertex::{self, AttributeInfo, VertexLayout},
+};
 
 /// Different type of shader
 /// that we can compile.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ShaderType {
     Vertex,
     Fragment,
+    Geometry,
+    TessControl,
+    TessEvaluation,
+
+    /// A compute shader, linked alone and run with `Shader::dispatch`.
+    /// Needs an OpenGL 4.3 context (see `WindowConfig::with_gl_version`).
+    Compute,
 }
 
-/// A shader error.
-pub struct ShaderError {
-    /// Kind of error.
-    pub kind: String,
+impl ShaderType {
+    /// The GL enum of the stage.
+    pub fn gl_enum(&self) -> GLenum {
+        match self {
+            ShaderType::Vertex         => gl::VERTEX_SHADER,
+            ShaderType::Fragment       => gl::FRAGMENT_SHADER,
+            ShaderType::Geometry       => gl::GEOMETRY_SHADER,
+            ShaderType::TessControl    => gl::TESS_CONTROL_SHADER,
+            ShaderType::TessEvaluation => gl::TESS_EVALUATION_SHADER,
+            ShaderType::Compute        => gl::COMPUTE_SHADER,
+        }
+    }
 
-    /// The error description.
+    /// The stage name, used in error messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderType::Vertex         => "Vertex",
+            ShaderType::Fragment       => "Fragment",
+            ShaderType::Geometry       => "Geometry",
+            ShaderType::TessControl    => "Tessellation control",
+            ShaderType::TessEvaluation => "Tessellation evaluation",
+            ShaderType::Compute        => "Compute",
+        }
+    }
+}
+
+/// A message of a shader compiler log, with the place it refers to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderDiagnostic {
+    /// The file the message refers to, `None` if unknown.
+    pub file: Option<PathBuf>,
+
+    /// The line (starting from 1) the message refers to, `None` if unknown.
+    pub line: Option<u32>,
+
+    /// The message, without the line reference.
     pub message: String,
 }
 
-impl ShaderError {
-    /// Create new shader error.
-    /// 
+impl ShaderDiagnostic {
+    /// Split a compiler log in diagnostics, one per non empty line.
+    ///
     /// # Arguments
-    /// * `kind` - The kind of error.
-    /// * `message` - The error description.
-    pub fn new(kind: &str, message: &str) -> ShaderError {
-        ShaderError {
-            kind: String::from(kind),
-            message: String::from(message),
+    /// * `log` - The compiler log.
+    pub fn parse(log: &str) -> Vec<ShaderDiagnostic> {
+        log.lines().filter(|l| !l.trim().is_empty()).map(|line| {
+            match preprocessor::find_line_ref(line) {
+                Some((_, end, n)) => {
+                    let rest = &line[end..];
+
+                    // Mesa follows the line with the column: `0:7(5): error`.
+                    let rest = match rest.strip_prefix('(').and_then(|r| r.split_once(')')) {
+                        Some((column, r)) if !column.is_empty() && column.bytes().all(|b| b.is_ascii_digit()) => r,
+                        _ => rest,
+                    };
+
+                    ShaderDiagnostic {
+                        file: None,
+                        line: Some(n),
+                        message: rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace()).to_string(),
+                    }
+                },
+                None => ShaderDiagnostic {
+                    file: None,
+                    line: None,
+                    message: line.trim().to_string(),
+                },
+            }
+        }).collect()
+    }
+}
+
+/// A shader error.
+#[derive(Debug)]
+pub enum ShaderError {
+    /// A source file can't be read.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    /// A source file isn't valid UTF-8.
+    InvalidUtf8 {
+        path: PathBuf,
+    },
+
+    /// A source contains a NUL byte, GL sources are C strings.
+    NulInSource {
+        stage: ShaderType,
+
+        /// The byte position of the NUL in the preprocessed source.
+        position: usize,
+    },
+
+    /// An `#include` is malformed or includes itself.
+    Include(String),
+
+    /// An included file can't be read.
+    IncludeFile {
+        /// The `file:line` of the `#include`.
+        location: String,
+
+        /// Why the file can't be read (`Io` or `InvalidUtf8`).
+        error: Box<ShaderError>,
+    },
+
+    /// A stage failed to compile.
+    Compile {
+        stage: ShaderType,
+
+        /// The compiler log, with the original file and line numbers.
+        log: String,
+
+        /// The messages of the log.
+        diagnostics: Vec<ShaderDiagnostic>,
+    },
+
+    /// The program failed to link.
+    Link {
+        log: String,
+    },
+
+    /// The stages can't be linked together.
+    InvalidStages(String),
+
+    /// An uniform value doesn't match the shader uniforms.
+    Uniform(String),
+
+    /// A vertex layout doesn't provide the attributes the shader reads.
+    Layout(String),
+
+    /// The shader isn't a compute shader and can't be dispatched.
+    NotCompute,
+}
+
+impl std::fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderError::Io { path, error } => write!(f, "Can't read '{}': {}", path.display(), error),
+            ShaderError::InvalidUtf8 { path } => write!(f, "'{}' is not valid UTF-8", path.display()),
+            ShaderError::NulInSource { stage, position } => write!(f, "{} shader source contains a NUL byte at {}", stage.name(), position),
+            ShaderError::Include(msg) => write!(f, "{}", msg),
+            ShaderError::IncludeFile { location, error } => write!(f, "{}: can't include: {}", location, error),
+            ShaderError::Compile { stage, log, .. } => write!(f, "{} shader compilation error:\n{}", stage.name(), log),
+            ShaderError::Link { log } => write!(f, "Program link error:\n{}", log),
+            ShaderError::InvalidStages(msg) => write!(f, "{}", msg),
+            ShaderError::Uniform(msg) => write!(f, "{}", msg),
+            ShaderError::Layout(msg) => write!(f, "{}", msg),
+            ShaderError::NotCompute => write!(f, "Only compute shaders can be dispatched"),
         }
     }
 }
 
-impl From<std::io::Error> for ShaderError {
-    /// Convert an `std::io::Error` to a `ShaderError`
-    /// 
+impl std::error::Error for ShaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShaderError::Io { error, .. } => Some(error),
+            ShaderError::IncludeFile { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Read the info log of a shader or a program.
+///
+/// # Arguments
+/// * `object` - The shader or program ID.
+/// * `get_iv` - `gl::GetShaderiv` or `gl::GetProgramiv`.
+/// * `get_log` - `gl::GetShaderInfoLog` or `gl::GetProgramInfoLog`.
+unsafe fn info_log(
+    object: GLuint,
+    get_iv: unsafe fn(GLuint, GLenum, *mut GLint),
+    get_log: unsafe fn(GLuint, GLsizei, *mut GLsizei, *mut GLchar),
+) -> String {
+    let mut len = 0;
+    get_iv(object, gl::INFO_LOG_LENGTH, &mut len);
+
+    let mut infos = vec![0u8; len.max(1) as usize];
+    let mut written: GLsizei = 0;
+    get_log(object, infos.len() as GLsizei, &mut written, infos.as_mut_ptr() as *mut GLchar);
+
+    String::from_utf8_lossy(&infos[..written as usize]).into_owned()
+}
+
+/// Where the source of a stage comes from.
+#[derive(Clone)]
+enum StageSource {
+    File(String),
+    Source(String),
+}
+
+/// Compile and link any combination of shader stages.
+///
+/// Sources go through a `Preprocessor` first, so they can use `#include`
+/// and receive defines. Compilation errors refer to the original files.
+///
+/// ```ignore
+/// let shader = ShaderBuilder::new()
+///     .with_file(ShaderType::Vertex, "./shaders/grass/vert.glsl")
+///     .with_file(ShaderType::Geometry, "./shaders/grass/geom.glsl")
+///     .with_file(ShaderType::Fragment, "./shaders/grass/frag.glsl")
+///     .build()?;
+/// ```
+#[derive(Clone)]
+pub struct ShaderBuilder {
+    stages: Vec<(ShaderType, StageSource)>,
+    preprocessor: Preprocessor,
+}
+
+impl Default for ShaderBuilder {
+    fn default() -> Self {
+        ShaderBuilder::new()
+    }
+}
+
+impl ShaderBuilder {
+    /// Create new `ShaderBuilder` without stage.
+    pub fn new() -> Self {
+        Self {
+            stages: Vec::new(),
+            preprocessor: Preprocessor::new(),
+        }
+    }
+
+    /// Inject a define (`#define <name> <value>`) in every stage.
+    ///
+    /// # Arguments
+    /// * `name` - The define name.
+    /// * `value` - The define value, can be empty.
+    pub fn with_define(mut self, name: &str, value: &str) -> Self {
+        self.preprocessor = self.preprocessor.with_define(name, value);
+        self
+    }
+
+    /// Set the `#version` of the stages that don't have one (`"410 core"` by default).
+    ///
     /// # Arguments
-    /// * `error` - The error to convert.
-    fn from(error: std::io::Error) -> Self { 
-        ShaderError {
-            kind: String::from("io"),
-            message: error.to_string(),
+    /// * `version` - The version, like `"430 core"`.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.preprocessor = self.preprocessor.with_version(version);
+        self
+    }
+
+    /// Add a stage read from a file when the shader is built.
+    ///
+    /// # Arguments
+    /// * `stage` - The type of shader.
+    /// * `path` - The path of the shader source.
+    pub fn with_file(mut self, stage: ShaderType, path: &str) -> Self {
+        self.stages.push((stage, StageSource::File(String::from(path))));
+        self
+    }
+
+    /// Add a stage from its source.
+    ///
+    /// # Arguments
+    /// * `stage` - The type of shader.
+    /// * `src` - The shader source.
+    pub fn with_source(mut self, stage: ShaderType, src: &str) -> Self {
+        self.stages.push((stage, StageSource::Source(String::from(src))));
+        self
+    }
+
+    /// Check that the stages can be linked together.
+    fn validate(&self) -> Result<(), ShaderError> {
+        let has = |stage: ShaderType| self.stages.iter().any(|(s, _)| *s == stage);
+
+        for (i, (stage, _)) in self.stages.iter().enumerate() {
+            if self.stages[..i].iter().any(|(s, _)| s == stage) {
+                return Err(ShaderError::InvalidStages(format!("{} shader added twice", stage.name())))
+            }
         }
+
+        let msg = if self.stages.is_empty() {
+            "A shader needs at least one stage"
+        } else if has(ShaderType::Compute) && self.stages.len() > 1 {
+            "A compute shader can't be linked with other stages"
+        } else if !has(ShaderType::Compute) && !has(ShaderType::Vertex) {
+            "A shader needs a vertex stage"
+        } else if has(ShaderType::TessControl) && !has(ShaderType::TessEvaluation) {
+            "A tessellation control stage needs a tessellation evaluation stage"
+        } else {
+            return Ok(())
+        };
+
+        Err(ShaderError::InvalidStages(String::from(msg)))
     }
+
+    /// Run the preprocessor on the source of a stage.
+    fn preprocess(&self, stage: &ShaderType, source: &StageSource) -> Result<PreprocessedSource, ShaderError> {
+        match source {
+            StageSource::File(path) => self.preprocessor.process_file(path),
+            StageSource::Source(src) => self.preprocessor.process_source(src, &format!("<{} source>", stage.name())),
+        }
+    }
+
+    /// Compile and link the stages.
+    pub fn build(&self) -> Result<Shader, ShaderError> {
+        let (program, files) = self.compile()?;
+
+        Ok(Shader {
+            uniforms: RefCell::new(unsafe { Shader::introspect(program.get_id()) }),
+            attributes: RefCell::new(unsafe { Shader::introspect_attributes(program.get_id()) }),
+            program: RefCell::new(program),
+            stages: self.stages.iter().map(|(s, _)| *s).collect(),
+            files: RefCell::new(files),
+            builder: self.clone(),
+        })
+    }
+
+    /// Compile and link the stages into a new program.
+    ///
+    /// Returns the program and the files read to build it.
+    fn compile(&self) -> Result<(GlObject, Vec<PathBuf>), ShaderError> {
+        self.validate()?;
+
+        let mut shaders = Vec::with_capacity(self.stages.len());
+        let mut files = Vec::new();
+
+        for (stage, source) in self.stages.iter() {
+            let compiled = self.preprocess(stage, source).and_then(|src| {
+                // A source given as a string has no file, only its includes are files.
+                let skip = match source {
+                    StageSource::File(_) => 0,
+                    StageSource::Source(_) => 1,
+                };
+
+                for file in src.files.iter().skip(skip) {
+                    if !files.contains(file) {
+                        files.push(file.clone());
+                    }
+                }
+
+                unsafe { Shader::compile_shader(&src.code, stage) }.map_err(|e| src.map_error(e))
+            });
+
+            match compiled {
+                Ok(shader) => shaders.push(shader),
+                Err(e) => {
+                    for shader in shaders {
+                        unsafe { gl::DeleteShader(shader) };
+                    }
+
+                    return Err(e)
+                },
+            }
+        }
+
+        let program = unsafe { Shader::link_program(&shaders)? };
+        Ok((GlObject::new(GlObjectKind::Program, program), files))
+    }
+}
+
+/// A sorted set of defines (`(name, value)`), identifying a permutation of `ShaderVariants`.
+type VariantKey = Vec<(String, String)>;
+
+/// Compiled permutations of a shader, one per set of defines.
+///
+/// ```ignore
+/// let variants = ShaderVariants::new(ShaderBuilder::new().with_file(...).with_file(...));
+/// let lit = variants.get(&[("LIGHTING", "1")])?;
+/// ```
+pub struct ShaderVariants {
+    /// The stages, and the defines shared by every permutation.
+    builder: ShaderBuilder,
+
+    /// The compiled permutations, by sorted define set.
+    cache: RefCell<HashMap<VariantKey, Rc<Shader>>>,
 }
 
+impl ShaderVariants {
+    /// Create new `ShaderVariants` without any compiled permutation.
+    /// # Arguments
+    /// * `builder` - The stages, and the defines shared by every permutation.
+    pub fn new(builder: ShaderBuilder) -> Self {
+        Self {
+            builder,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
 
+    /// Get the permutation compiled with a set of defines, compiling it the first time.
+    /// # Arguments
+    /// * `defines` - The defines (`(name, value)`), in any order.
+    pub fn get(&self, defines: &[(&str, &str)]) -> Result<Rc<Shader>, ShaderError> {
+        let mut key = defines.iter()
+            .map(|(n, v)| (String::from(*n), String::from(*v)))
+            .collect::<VariantKey>();
+
+        key.sort();
+
+        if let Some(shader) = self.cache.borrow().get(&key) {
+            return Ok(shader.clone())
+        }
+
+        let builder = key.iter().fold(self.builder.clone(), |b, (n, v)| b.with_define(n, v));
+        let shader = Rc::new(builder.build()?);
+
+        self.cache.borrow_mut().insert(key, shader.clone());
+        Ok(shader)
+    }
+
+    /// The amount of compiled permutations.
+    pub fn len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// `true` if no permutation was compiled yet.
+    pub fn is_empty(&self) -> bool {
+        self.cache.borrow().is_empty()
+    }
+}
+
+/// A linked shader program.
+///
+/// Shaders are shared with an `Rc`, `reload` swaps the program
+/// in place so every material using the shader sees the new one.
 pub struct Shader {
-    /// The shader program id.
-    program: u32,
+    /// The shader program, deleted when dropped.
+    program: RefCell<GlObject>,
+
+    /// The active uniforms of the program, by name.
+    uniforms: RefCell<HashMap<String, UniformInfo>>,
+
+    /// The active vertex attributes of the program, by name.
+    attributes: RefCell<HashMap<String, AttributeInfo>>,
+
+    /// The stages linked in the program.
+    stages: Vec<ShaderType>,
+
+    /// The files read to build the program, includes too.
+    files: RefCell<Vec<PathBuf>>,
+
+    /// The stages sources and defines, to build the program again.
+    builder: ShaderBuilder,
 }
 
 impl Shader {
@@ -58,22 +474,275 @@ impl Shader {
     /// * `vertex_shader_path` - The path of the vertex shader.
     /// * `fragment_shader_path` - The path of the fragment shader.
     pub fn new(vertex_shader_path: &str, fragment_shader_path: &str) -> Result<Self, ShaderError> {
-        unsafe {
-            let vs_src = std::fs::read_to_string(vertex_shader_path)?;
-            let fs_src = std::fs::read_to_string(fragment_shader_path)?;
+        ShaderBuilder::new()
+            .with_file(ShaderType::Vertex, vertex_shader_path)
+            .with_file(ShaderType::Fragment, fragment_shader_path)
+            .build()
+    }
+
+    /// Returns a shader compiled from sources, for sources embedded with `include_str!`.
+    ///
+    /// # Arguments
+    /// * `vertex_src` - The vertex shader source.
+    /// * `fragment_src` - The fragment shader source.
+    pub fn from_source(vertex_src: &str, fragment_src: &str) -> Result<Self, ShaderError> {
+        ShaderBuilder::new()
+            .with_source(ShaderType::Vertex, vertex_src)
+            .with_source(ShaderType::Fragment, fragment_src)
+            .build()
+    }
+
+    /// The stages linked in the program.
+    pub fn get_stages(&self) -> &[ShaderType] {
+        &self.stages
+    }
+
+    /// The files read to build the program (stage files and includes).
+    pub fn get_source_paths(&self) -> Ref<'_, Vec<PathBuf>> {
+        self.files.borrow()
+    }
+
+    /// Build the program again from its sources.
+    ///
+    /// The new program replaces the current one only if it compiles and
+    /// links, otherwise the current one is kept and the error returned.
+    /// Uniform values set on the old program are lost, materials upload
+    /// theirs again when they are applied.
+    ///
+    /// The meshes that use the shader are not checked again, a new program
+    /// may read attributes their vertices don't provide: use `reload_compatible`.
+    pub fn reload(&self) -> Result<(), ShaderError> {
+        self.reload_compatible(&[])
+    }
+
+    /// Build the program again from its sources, like `reload`.
+    ///
+    /// The new program is also rejected (and the current one kept) if one
+    /// of the vertex layouts doesn't provide the attributes it reads.
+    ///
+    /// # Arguments
+    /// * `layouts` - The vertex layouts of the meshes that use the shader.
+    pub fn reload_compatible(&self, layouts: &[VertexLayout]) -> Result<(), ShaderError> {
+        let (program, files) = self.builder.compile()?;
+        let attributes = unsafe { Shader::introspect_attributes(program.get_id()) };
+
+        for layout in layouts {
+            vertex::check_compatibility(layout, attributes.values()).map_err(ShaderError::Layout)?;
+        }
+
+        *self.uniforms.borrow_mut() = unsafe { Shader::introspect(program.get_id()) };
+        *self.attributes.borrow_mut() = attributes;
+        *self.program.borrow_mut() = program;
+        *self.files.borrow_mut() = files;
+        Ok(())
+    }
+
+    /// `true` if the shader is a compute shader.
+    pub fn is_compute(&self) -> bool {
+        self.stages.contains(&ShaderType::Compute)
+    }
 
-            let vs = Shader::compile_shader(&vs_src, &ShaderType::Vertex)?;
-            let fs = Shader::compile_shader(&fs_src, &ShaderType::Fragment)?;
+    /// Run a compute shader.
+    ///
+    /// Bind the storage buffers and images first. Call `memory_barrier`
+    /// before reading what the shader wrote.
+    ///
+    /// # Arguments
+    /// * `groups` - The amount of work groups in each dimension.
+    pub fn dispatch(&self, groups: (u32, u32, u32)) -> Result<(), ShaderError> {
+        if !self.is_compute() {
+            return Err(ShaderError::NotCompute)
+        }
+
+        self.use_it();
+        unsafe { gl::DispatchCompute(groups.0, groups.1, groups.2) };
+        Ok(())
+    }
+
+    /// Wait for the writes of the dispatched compute shaders.
+    ///
+    /// # Arguments
+    /// * `barriers` - What is about to be read (`gl::SHADER_STORAGE_BARRIER_BIT`, ...).
+    pub fn memory_barrier(barriers: GLbitfield) {
+        unsafe { gl::MemoryBarrier(barriers) };
+    }
+
+    /// Read the active uniforms of a linked program.
+    ///
+    /// # Arguments
+    /// * `program` - The program ID.
+    unsafe fn introspect(program: u32) -> HashMap<String, UniformInfo> {
+        let mut count = 0;
+        let mut max_len = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_len);
+
+        let mut uniforms = HashMap::with_capacity(count as usize);
+        let mut name = vec![0u8; max_len.max(1) as usize];
+
+        for i in 0..count as GLuint {
+            let mut len: GLsizei = 0;
+            let mut size: GLint = 0;
+            let mut gl_type: GLenum = 0;
+
+            gl::GetActiveUniform(program, i, name.len() as GLsizei, &mut len, &mut size, &mut gl_type, name.as_mut_ptr() as *mut GLchar);
+
+            let full_name = String::from_utf8_lossy(&name[..len as usize]).into_owned();
+            let c_name = CString::new(full_name.as_bytes()).unwrap();
+            let location = gl::GetUniformLocation(program, c_name.as_ptr());
+
+            // Uniforms in blocks have no location and can't be set with `glUniform*`.
+            if location < 0 {
+                continue
+            }
+
+            let name = full_name.trim_end_matches("[0]").to_string();
+            uniforms.insert(name.clone(), UniformInfo { name, gl_type, size, location });
+        }
+
+        uniforms
+    }
+
+    /// Read the active vertex attributes of a linked program.
+    ///
+    /// # Arguments
+    /// * `program` - The program ID.
+    unsafe fn introspect_attributes(program: u32) -> HashMap<String, AttributeInfo> {
+        let mut count = 0;
+        let mut max_len = 0;
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTES, &mut count);
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_len);
+
+        let mut attributes = HashMap::with_capacity(count as usize);
+        let mut name = vec![0u8; max_len.max(1) as usize];
+
+        for i in 0..count as GLuint {
+            let mut len: GLsizei = 0;
+            let mut size: GLint = 0;
+            let mut gl_type: GLenum = 0;
+
+            gl::GetActiveAttrib(program, i, name.len() as GLsizei, &mut len, &mut size, &mut gl_type, name.as_mut_ptr() as *mut GLchar);
+
+            let name = String::from_utf8_lossy(&name[..len as usize]).into_owned();
+            let c_name = CString::new(name.as_bytes()).unwrap();
+            let location = gl::GetAttribLocation(program, c_name.as_ptr());
+
+            // Built-in inputs (`gl_VertexID`, ...) have no location.
+            if location < 0 {
+                continue
+            }
+
+            attributes.insert(name.clone(), AttributeInfo { name, gl_type, location });
+        }
+
+        attributes
+    }
+
+    /// The active vertex attributes of the shader, by name.
+    pub fn get_attributes(&self) -> Ref<'_, HashMap<String, AttributeInfo>> {
+        self.attributes.borrow()
+    }
 
-            let program = Shader::create_and_link_program(vs, fs)?;
+    /// The active uniforms of the shader, by name.
+    pub fn get_uniforms(&self) -> Ref<'_, HashMap<String, UniformInfo>> {
+        self.uniforms.borrow()
+    }
+
+    /// Get an active uniform of the shader.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader).
+    pub fn get_uniform(&self, name: &str) -> Option<Ref<'_, UniformInfo>> {
+        Ref::filter_map(self.uniforms.borrow(), |u| u.get(name)).ok()
+    }
 
-            Ok(Self { program: program })
+    /// Get the location of an uniform, `-1` if the shader has no such active uniform.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader).
+    pub fn get_uniform_location(&self, name: &str) -> GLint {
+        match self.uniforms.borrow().get(name) {
+            Some(uniform) => uniform.location,
+            None => -1,
         }
     }
 
+    /// Set an uniform value. The shader must be in use (see `use_it`).
+    ///
+    /// Fails if the shader has no active uniform with this name or if
+    /// the value type doesn't match the uniform type. Samplers are set
+    /// with an `i32` (the texture unit).
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader) to set.
+    /// * `value` - The new value of the uniform variable.
+    pub fn set_uniform<T: UniformType>(&self, name: &str, value: T) -> Result<(), ShaderError> {
+        self.set_uniform_array(name, &[value])
+    }
+
+    /// Set an uniform value if the shader has such active uniform, for the
+    /// uniforms set to every shader (like `view_projection`) that a shader may not use.
+    ///
+    /// Fails if the value type doesn't match the uniform type.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader) to set.
+    /// * `value` - The new value of the uniform variable.
+    pub fn set_uniform_if_active<T: UniformType>(&self, name: &str, value: T) -> Result<(), ShaderError> {
+        if self.get_uniform(name).is_none() {
+            return Ok(())
+        }
+
+        self.set_uniform(name, value)
+    }
+
+    /// Set the values of an uniform array, starting from the first element.
+    /// The shader must be in use (see `use_it`).
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uniform array (in the shader) to set.
+    /// * `values` - The new values of the uniform array.
+    pub fn set_uniform_array<T: UniformType>(&self, name: &str, values: &[T]) -> Result<(), ShaderError> {
+        let location = self.check_uniform::<T>(name, values.len())?.location;
+
+        unsafe { T::upload(location, values) };
+        Ok(())
+    }
+
+    /// Check that values can be uploaded to an uniform.
+    ///
+    /// Returns the uniform if it is active and if its type and size match.
+    /// # Arguments
+    /// * `name` - The name of the uniform variable (in the shader).
+    /// * `count` - The amount of values.
+    pub fn check_uniform<T: UniformType>(&self, name: &str, count: usize) -> Result<Ref<'_, UniformInfo>, ShaderError> {
+        let uniform = match self.get_uniform(name) {
+            Some(uniform) => uniform,
+            None => return Err(ShaderError::Uniform(format!("The shader has no active uniform '{}'", name))),
+        };
+
+        if !uniform::accepts(uniform.gl_type, T::GL_TYPE) {
+            let msg = format!(
+                "Uniform '{}' is a {} but the value is a {}",
+                name,
+                uniform::glsl_type_name(uniform.gl_type),
+                uniform::glsl_type_name(T::GL_TYPE),
+            );
+
+            return Err(ShaderError::Uniform(msg))
+        }
+
+        if count == 0 || count > uniform.size as usize {
+            let msg = format!("Uniform '{}' holds {} value(s) but got {}", name, uniform.size, count);
+            return Err(ShaderError::Uniform(msg))
+        }
+
+        Ok(uniform)
+    }
+
     /// Just return the shader program id.
     pub fn get_program(&self) -> GLuint {
-        self.program
+        self.program.borrow().get_id()
     }
 
     /// Use the current shader.
@@ -86,14 +755,19 @@ impl Shader {
     /// # Arguments
     /// * `src` - The shader source that we want to compile.
     /// * `shader_type` - The type of shader.
+    ///
+    /// # Safety
+    /// A GL context supporting the stage must be current on this thread with
+    /// the GL methods loaded. The returned shader must be deleted by the caller
+    /// (`link_program` does it).
     pub unsafe fn compile_shader(src: &str, shader_type: &ShaderType) -> Result<u32, ShaderError> {
-        
-        let shader = gl::CreateShader(match shader_type { 
-            ShaderType::Fragment => gl::FRAGMENT_SHADER, 
-            ShaderType::Vertex   => gl::VERTEX_SHADER,
-        });
+        let c_str_shader = match CString::new(src.as_bytes()) {
+            Ok(c_str) => c_str,
+            Err(e) => return Err(ShaderError::NulInSource { stage: *shader_type, position: e.nul_position() }),
+        };
+
+        let shader = gl::CreateShader(shader_type.gl_enum());
 
-        let c_str_shader = CString::new(src.as_bytes()).unwrap();
         gl::ShaderSource(shader, 1, &c_str_shader.as_ptr(), std::ptr::null());
         gl::CompileShader(shader);
 
@@ -102,23 +776,14 @@ impl Shader {
         gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
 
         if success != gl::TRUE as GLint {
-            let mut len = 0;
-            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-
-            let mut infos = Vec::with_capacity(len as usize);
-            infos.set_len((len as usize) - 1);
-
-            gl::GetShaderInfoLog(shader, len, std::ptr::null_mut(), infos.as_mut_ptr() as *mut GLchar);
-            
-            let shader_type_name = match shader_type { 
-                ShaderType::Vertex   => "Vertex", 
-                ShaderType::Fragment => "Fragment",
-            };
-
-            let shader_result_str = std::str::from_utf8(infos.as_slice()).unwrap();
-            let msg = format!("{} shader compilation error:\n{}", shader_type_name, shader_result_str);
-
-            Err(ShaderError::new("Shader", &msg))
+            let log = info_log(shader, gl::GetShaderiv, gl::GetShaderInfoLog);
+            gl::DeleteShader(shader);
+
+            Err(ShaderError::Compile {
+                stage: *shader_type,
+                diagnostics: ShaderDiagnostic::parse(&log),
+                log,
+            })
         } else {
             Ok(shader)
         }
@@ -129,36 +794,47 @@ impl Shader {
     /// # Arguments
     /// * `vertex_shader` - The vertex shader ID.
     /// * `fragment_shader` - The fragment shader ID.
+    ///
+    /// # Safety
+    /// Same as `link_program`.
     pub unsafe fn create_and_link_program(vertex_shader: u32, fragment_shader: u32) -> Result<u32, ShaderError> {
+        Shader::link_program(&[vertex_shader, fragment_shader])
+    }
+
+    /// Create program id and link compiled shaders to it.
+    ///
+    /// The shaders are deleted, whether the link succeed or not.
+    ///
+    /// # Arguments
+    /// * `shaders` - The compiled shader IDs.
+    ///
+    /// # Safety
+    /// The GL context that compiled the shaders must be current on this thread.
+    /// The shaders are deleted and must not be used afterwards, the returned
+    /// program must be deleted by the caller (wrap it in a `GlObject`).
+    pub unsafe fn link_program(shaders: &[u32]) -> Result<u32, ShaderError> {
         let program = gl::CreateProgram();
-        gl::AttachShader(program, vertex_shader as GLuint);
-        gl::AttachShader(program, fragment_shader as GLuint);
+
+        for shader in shaders {
+            gl::AttachShader(program, *shader as GLuint);
+        }
+
         gl::LinkProgram(program);
 
+        for shader in shaders {
+            gl::DeleteShader(*shader);
+        }
+
         let mut success = gl::FALSE as GLint;
 
         gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
 
         if success != gl::TRUE as GLint {
-            let mut len = 0;
-            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
-
-            let mut infos = Vec::with_capacity(len as usize);
-            infos.set_len((len as usize) - 1);
-
-            gl::GetProgramInfoLog(program, len, std::ptr::null_mut(), infos.as_mut_ptr() as *mut GLchar);
-
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
+            let log = info_log(program, gl::GetProgramiv, gl::GetProgramInfoLog);
+            gl::DeleteProgram(program);
 
-            let shader_result_str = std::str::from_utf8(infos.as_slice()).unwrap();
-            let msg = format!("Program shader compilation error:\n{}", shader_result_str);
-
-            Err(ShaderError::new("Program", &msg))
+            Err(ShaderError::Link { log })
         } else {
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
-
             Ok(program)
         }
     }
@@ -169,11 +845,10 @@ impl Shader {
     /// * `name` - The name of the uniform variable (in the shaders) to set.
     /// * `value` - The new value of the uniform variable.
     /// * `transpose` - `true` if the matrix must be transposed otherwise `false`.
+    #[deprecated(note = "use `set_uniform`, it reports the missing uniforms and the type mismatches")]
     pub fn set_matrix4(&self, name: &str, value: &Matrix4<f32>, transpose: bool) {
-        unsafe {
-            let name = CString::new(name).unwrap();
-            let location = gl::GetUniformLocation(self.program, name.as_ptr());
-            gl::UniformMatrix4fv(location, 1, transpose as GLboolean, value.as_ptr());
+        if let Ok(uniform) = self.check_uniform::<Matrix4<f32>>(name, 1) {
+            unsafe { gl::UniformMatrix4fv(uniform.location, 1, transpose as GLboolean, value.as_ptr()) };
         }
     }
 
@@ -182,12 +857,9 @@ impl Shader {
     /// # Arguments
     /// * `name` - The name of the uniform variable (in the shader) to set.
     /// * `value` - The new value of the uniform variable.
+    #[deprecated(note = "use `set_uniform`, it reports the missing uniforms and the type mismatches")]
     pub fn set_float(&self, name: &str, value: f32) {
-        unsafe {
-            let name = CString::new(name).unwrap();
-            let location = gl::GetUniformLocation(self.program, name.as_ptr());
-            gl::Uniform1f(location, value);
-        }
+        let _ = self.set_uniform(name, value);
     }
 
     /// Set an uniform (`vector1`) values to shaders.
@@ -195,12 +867,10 @@ impl Shader {
     /// # Arguments
     /// * `name` - The name of the uniform variable (in the shader) to set.
     /// * `values` - The new value of the uniform variable.
+    #[deprecated(note = "use `set_uniform_array`, it reports the missing uniforms and the type mismatches")]
     pub fn set_vec1(&self, name: &str, values: &[Vector1<f32>]) {
-        unsafe {
-            let name = CString::new(name).unwrap();
-            let location = gl::GetUniformLocation(self.program, name.as_ptr());
-            gl::Uniform1fv(location, values.len() as i32, &values[0] as *const Vector1<f32> as *const f32);
-        }
+        let values = values.iter().map(|v| v.x).collect::<Vec<f32>>();
+        let _ = self.set_uniform_array(name, &values);
     }
 
     /// Set an uniform (`vector2`) values to shaders.
@@ -208,12 +878,9 @@ impl Shader {
     /// # Arguments
     /// * `name` - The name of the uniform variable (in the shader) to set.
     /// * `values` - The new value of the uniform variable.
+    #[deprecated(note = "use `set_uniform_array`, it reports the missing uniforms and the type mismatches")]
     pub fn set_vec2(&self, name: &str, values: &[Vector2<f32>]) {
-        unsafe {
-            let name = CString::new(name).unwrap();
-            let location = gl::GetUniformLocation(self.program, name.as_ptr());
-            gl::Uniform2fv(location, values.len() as i32, &values[0] as *const Vector2<f32> as *const f32);
-        }
+        let _ = self.set_uniform_array(name, values);
     }
 
     /// Set an uniform (`vector3`) values to shaders.
@@ -221,11 +888,94 @@ impl Shader {
     /// # Arguments
     /// * `name` - The name of the uniform variable (in the shader) to set.
     /// * `values` - The new value of the uniform variable.
+    #[deprecated(note = "use `set_uniform_array`, it reports the missing uniforms and the type mismatches")]
     pub fn set_vec3(&self, name: &str, values: &[Vector3<f32>]) {
-        unsafe {
-            let name = CString::new(name).unwrap();
-            let location = gl::GetUniformLocation(self.program, name.as_ptr());
-            gl::Uniform3fv(location, values.len() as i32, &values[0] as *const Vector3<f32> as *const f32);
+        let _ = self.set_uniform_array(name, values);
+    }
+}
+
+#[cfg(test)]
+impl Shader {
+    /// A shader without GL program, for the tests that run without GL context.
+    ///
+    /// # Arguments
+    /// * `uniforms` - The active uniforms.
+    /// * `attributes` - The active vertex attributes.
+    pub fn headless(uniforms: Vec<UniformInfo>, attributes: Vec<AttributeInfo>) -> Self {
+        Self {
+            program: RefCell::new(GlObject::null(GlObjectKind::Program)),
+            uniforms: RefCell::new(uniforms.into_iter().map(|u| (u.name.clone(), u)).collect()),
+            attributes: RefCell::new(attributes.into_iter().map(|a| (a.name.clone(), a)).collect()),
+            stages: vec![ShaderType::Vertex, ShaderType::Fragment],
+            files: RefCell::new(Vec::new()),
+            builder: ShaderBuilder::new(),
+        }
+    }
+
+    /// Set the source files of a shader, as if it was built from them.
+    ///
+    /// # Arguments
+    /// * `paths` - The source files.
+    pub fn with_source_paths(self, paths: Vec<PathBuf>) -> Self {
+        *self.files.borrow_mut() = paths;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn parses_nvidia_logs() {
+        let log = "0(12) : error C1008: undefined variable \"color\"\n\n0(14) : warning C7022: unrecognized profile\n";
+
+        assert_eq!(ShaderDiagnostic::parse(log), vec![
+            ShaderDiagnostic { file: None, line: Some(12), message: String::from("error C1008: undefined variable \"color\"") },
+            ShaderDiagnostic { file: None, line: Some(14), message: String::from("warning C7022: unrecognized profile") },
+        ]);
+    }
+
+    #[test]
+    fn parses_mesa_logs() {
+        let log = "0:7(5): error: `color' undeclared\n0:9: warning: unused variable";
+
+        assert_eq!(ShaderDiagnostic::parse(log), vec![
+            ShaderDiagnostic { file: None, line: Some(7), message: String::from("error: `color' undeclared") },
+            ShaderDiagnostic { file: None, line: Some(9), message: String::from("warning: unused variable") },
+        ]);
+    }
+
+    #[test]
+    fn keeps_log_lines_without_reference() {
+        assert_eq!(ShaderDiagnostic::parse("  Vertex info: internal error  "), vec![
+            ShaderDiagnostic { file: None, line: None, message: String::from("Vertex info: internal error") },
+        ]);
+    }
+
+    #[test]
+    fn rejects_nul_in_source() {
+        // The source is checked before any GL call.
+        match unsafe { Shader::compile_shader("void main() {}\0", &ShaderType::Fragment) } {
+            Err(ShaderError::NulInSource { stage, position }) => {
+                assert_eq!(stage, ShaderType::Fragment);
+                assert_eq!(position, 14);
+            },
+            _ => panic!("the NUL byte was not detected"),
         }
     }
+
+    #[test]
+    fn include_errors_keep_their_location() {
+        let error = Preprocessor::new()
+            .process_source("float a;\n#include \"missing.glsl\"", "main.glsl")
+            .err()
+            .expect("the include should fail");
+
+        assert!(error.to_string().starts_with("main.glsl:2: can't include: Can't read 'missing.glsl'"), "{}", error);
+
+        let io = error.source().and_then(Error::source).and_then(|e| e.downcast_ref::<std::io::Error>());
+        assert_eq!(io.map(std::io::Error::kind), Some(std::io::ErrorKind::NotFound));
+    }
 }
@@ -0,0 +1,242 @@
+
+use crate::core::gl::{
+    object::{GlObject, GlObjectKind},
+    shader::Shader,
+    texture::Texture,
+    vertex::Vertex,
+};
+use gl::types::{
+    GLsizei,
+    GLsizeiptr,
+};
+
+use cgmath::{
+    Vector2,
+    Vector4,
+};
+
+/// A vertex of a batched sprite quad.
+///
+/// Its attributes are the position (location 0),
+/// the uv coordinates (location 1) and the color (location 2).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct SpriteVertex {
+    /// The vertex position.
+    pub pos:    Vector2<f32>,
+
+    /// The vertex uv coordinates.
+    pub uv:     Vector2<f32>,
+
+    /// The vertex color.
+    pub color:  Vector4<f32>,
+}
+
+impl SpriteVertex {
+    /// Create new `SpriteVertex`
+    /// # Arguments
+    /// * `pos` - The vertex position.
+    /// * `uv` - The vertex uv coordinates.
+    /// * `color` - The vertex color.
+    pub fn new(pos: Vector2<f32>, uv: Vector2<f32>, color: Vector4<f32>) -> Self {
+        Self {
+            pos,
+            uv,
+            color,
+        }
+    }
+}
+
+crate::impl_vertex!(SpriteVertex { pos => 0, uv => 1, color => 2 });
+
+/// The indices of two counter clockwise triangles per quad, for a certain amount of quads.
+///
+/// The quad vertices are the bottom left, top left, top right and bottom right corners.
+/// # Arguments
+/// * `quads` - The amount of quads.
+fn quad_indices(quads: usize) -> Vec<u32> {
+    let mut indices = Vec::<u32>::with_capacity(quads * 6);
+
+    for i in 0..quads as u32 {
+        let v = i * 4;
+        indices.extend_from_slice(&[v, v + 2, v + 1, v, v + 3, v + 2]);
+    }
+
+    indices
+}
+
+/// Accumulate sprite quads into a single dynamic
+/// vertex buffer and draw them with one draw call.
+///
+/// GPU buffers are created on the first flush and
+/// grow when more quads are pushed than they can hold.
+pub struct SpriteBatch {
+    /// The quads waiting to be drawn.
+    vertices: Vec<SpriteVertex>,
+
+    /// The amount of quads the GPU buffers can hold.
+    capacity: usize,
+
+    pub vbo: GlObject,
+    pub vao: GlObject,
+    pub ebo: GlObject,
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        SpriteBatch::new()
+    }
+}
+
+impl SpriteBatch {
+    /// Create new empty `SpriteBatch`.
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            capacity: 0,
+            vbo: GlObject::null(GlObjectKind::Buffer),
+            vao: GlObject::null(GlObjectKind::VertexArray),
+            ebo: GlObject::null(GlObjectKind::Buffer),
+        }
+    }
+
+    /// Add a quad to the batch.
+    /// # Arguments
+    /// * `quad` - The four vertices of the quad.
+    pub fn push(&mut self, quad: [SpriteVertex; 4]) {
+        self.vertices.extend(IntoIterator::into_iter(quad));
+    }
+
+    /// The amount of quads waiting to be drawn.
+    pub fn len(&self) -> usize {
+        self.vertices.len() / 4
+    }
+
+    /// `true` if there is no quad waiting to be drawn.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Draw all the pushed quads with a single draw call and clear the batch.
+    /// # Arguments
+    /// * `shader` - The shader used to draw the quads.
+    /// * `texture` - The texture sampled by the quads.
+    pub fn flush(&mut self, shader: &Shader, texture: &Texture) {
+        if self.is_empty() {
+            return
+        }
+
+        let quads = self.len();
+
+        unsafe {
+            self.reserve(quads);
+
+            let buff_size = self.vertices.len() * std::mem::size_of::<SpriteVertex>();
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo.get_id());
+            gl::BufferSubData(gl::ARRAY_BUFFER, 0, buff_size as GLsizeiptr, self.vertices.as_ptr() as *const gl::types::GLvoid);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            shader.use_it();
+            texture.bind(0);
+
+            gl::BindVertexArray(self.vao.get_id());
+            gl::DrawElements(gl::TRIANGLES, (quads * 6) as GLsizei, gl::UNSIGNED_INT, std::ptr::null());
+            gl::BindVertexArray(0);
+        }
+
+        self.vertices.clear();
+    }
+
+    /// Make sure the GPU buffers can hold a certain amount of quads.
+    /// # Arguments
+    /// * `quads` - The amount of quads.
+    unsafe fn reserve(&mut self, quads: usize) {
+        if quads <= self.capacity {
+            return
+        }
+
+        if self.vao.is_null() {
+            self.vao = GlObject::generate(GlObjectKind::VertexArray);
+            self.vbo = GlObject::generate(GlObjectKind::Buffer);
+            self.ebo = GlObject::generate(GlObjectKind::Buffer);
+        }
+
+        self.capacity = quads.max(self.capacity * 2).max(64);
+
+        let indices = quad_indices(self.capacity);
+        let buff_size = self.capacity * 4 * std::mem::size_of::<SpriteVertex>();
+        let i_buff_size = indices.len() * std::mem::size_of::<u32>();
+
+        gl::BindVertexArray(self.vao.get_id());
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo.get_id());
+        gl::BufferData(gl::ARRAY_BUFFER, buff_size as GLsizeiptr, std::ptr::null(), gl::DYNAMIC_DRAW);
+
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo.get_id());
+        gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, i_buff_size as GLsizeiptr, indices.as_ptr() as *const gl::types::GLvoid, gl::STATIC_DRAW);
+
+        SpriteVertex::layout().apply();
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        gl::BindVertexArray(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad(x: f32) -> [SpriteVertex; 4] {
+        let color = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
+        [
+            SpriteVertex::new(Vector2::new(x, 0.0), Vector2::new(0.0, 0.0), color),
+            SpriteVertex::new(Vector2::new(x, 1.0), Vector2::new(0.0, 1.0), color),
+            SpriteVertex::new(Vector2::new(x + 1.0, 1.0), Vector2::new(1.0, 1.0), color),
+            SpriteVertex::new(Vector2::new(x + 1.0, 0.0), Vector2::new(1.0, 0.0), color),
+        ]
+    }
+
+    #[test]
+    fn quads_are_two_triangles() {
+        assert_eq!(quad_indices(2), vec![0, 2, 1, 0, 3, 2, 4, 6, 5, 4, 7, 6]);
+        assert!(quad_indices(0).is_empty());
+    }
+
+    #[test]
+    fn layout_matches_the_sprite_shader() {
+        let layout = SpriteVertex::layout();
+
+        assert!(layout.validate().is_ok());
+        assert_eq!(layout.get_stride(), 32);
+        assert_eq!(layout.get_attribute(1).unwrap().offset, 8);
+        assert_eq!(layout.get_attribute(2).unwrap().components, 4);
+    }
+
+    #[test]
+    fn pushed_quads_are_kept_in_order() {
+        let mut batch = SpriteBatch::new();
+        assert!(batch.is_empty());
+
+        batch.push(quad(0.0));
+        batch.push(quad(5.0));
+
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch.vertices.len(), 8);
+        assert_eq!(batch.vertices[4].pos, Vector2::new(5.0, 0.0));
+        assert_eq!(batch.vertices[6].pos, Vector2::new(6.0, 1.0));
+    }
+
+    #[test]
+    fn empty_flush_creates_no_buffers() {
+        let mut batch = SpriteBatch::new();
+
+        // Without quads nothing is drawn, so no GL call is made.
+        batch.flush(&Shader::headless(Vec::new(), Vec::new()), &Texture::headless(1, 1));
+        assert!(batch.vao.is_null());
+    }
+}
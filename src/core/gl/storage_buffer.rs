@@ -0,0 +1,109 @@
+use gl::types::{ GLintptr, GLsizeiptr, GLuint };
+
+use crate::core::gl::object::{GlObject, GlObjectKind};
+
+/// A shader storage buffer (SSBO), read and written by shaders.
+///
+/// Needs an OpenGL 4.3 context (see `WindowConfig::with_gl_version`).
+pub struct StorageBuffer {
+    /// The buffer, deleted when dropped.
+    buffer: GlObject,
+
+    /// The buffer size in bytes.
+    size: usize,
+}
+
+impl StorageBuffer {
+    /// Create a zeroed storage buffer.
+    ///
+    /// # Arguments
+    /// * `size` - The buffer size in bytes.
+    pub fn new(size: usize) -> Self {
+        StorageBuffer::create(size, std::ptr::null())
+    }
+
+    /// Create a storage buffer that contains some values.
+    ///
+    /// # Arguments
+    /// * `values` - The initial content of the buffer, laid out like the shader expects (std430).
+    pub fn from_data<T: Copy>(values: &[T]) -> Self {
+        StorageBuffer::create(std::mem::size_of_val(values), values.as_ptr() as *const gl::types::GLvoid)
+    }
+
+    fn create(size: usize, data: *const gl::types::GLvoid) -> Self {
+        let buffer = GlObject::generate(GlObjectKind::Buffer);
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, buffer.get_id());
+            gl::BufferData(gl::SHADER_STORAGE_BUFFER, size as GLsizeiptr, data, gl::DYNAMIC_COPY);
+
+            if data.is_null() {
+                let zero = 0u32;
+                gl::ClearBufferData(gl::SHADER_STORAGE_BUFFER, gl::R32UI, gl::RED_INTEGER, gl::UNSIGNED_INT, &zero as *const u32 as *const gl::types::GLvoid);
+            }
+
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        Self {
+            buffer,
+            size,
+        }
+    }
+
+    /// Just return the buffer id.
+    pub fn get_id(&self) -> GLuint {
+        self.buffer.get_id()
+    }
+
+    /// The buffer size in bytes.
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// Bind the buffer to a storage block binding point (`layout(binding = n)`).
+    ///
+    /// # Arguments
+    /// * `binding` - The binding point.
+    pub fn bind(&self, binding: u32) {
+        unsafe { gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, self.get_id()) };
+    }
+
+    /// Write values in the buffer.
+    ///
+    /// Values that don't fit in the buffer are ignored.
+    ///
+    /// # Arguments
+    /// * `offset` - Where the values are written, in bytes.
+    /// * `values` - The values.
+    pub fn write<T: Copy>(&self, offset: usize, values: &[T]) {
+        let size = std::mem::size_of_val(values).min(self.size.saturating_sub(offset));
+
+        if size == 0 {
+            return
+        }
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.get_id());
+            gl::BufferSubData(gl::SHADER_STORAGE_BUFFER, offset as GLintptr, size as GLsizeiptr, values.as_ptr() as *const gl::types::GLvoid);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+    }
+
+    /// Read the whole buffer as values of a certain type.
+    ///
+    /// Call `Shader::memory_barrier` with `gl::BUFFER_UPDATE_BARRIER_BIT`
+    /// first to read what a compute shader wrote.
+    pub fn read<T: Copy + Default>(&self) -> Vec<T> {
+        let count = self.size / std::mem::size_of::<T>().max(1);
+        let mut values = vec![T::default(); count];
+
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.get_id());
+            gl::GetBufferSubData(gl::SHADER_STORAGE_BUFFER, 0, std::mem::size_of_val(values.as_slice()) as GLsizeiptr, values.as_mut_ptr() as *mut gl::types::GLvoid);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, 0);
+        }
+
+        values
+    }
+}
@@ -0,0 +1,214 @@
+use gl::types::{ GLenum, GLint, GLuint };
+
+use crate::core::gl::object::{GlObject, GlObjectKind};
+
+/// How a texture is sampled when it is
+/// minified or magnified.
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+/// How a compute shader accesses a texture bound as an image.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+/// A texture error.
+#[derive(Debug)]
+pub struct TextureError {
+    /// Kind of error.
+    pub kind: String,
+
+    /// The error description.
+    pub message: String,
+}
+
+impl TextureError {
+    /// Create new texture error.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of error.
+    /// * `message` - The error description.
+    pub fn new(kind: &str, message: &str) -> TextureError {
+        TextureError {
+            kind: String::from(kind),
+            message: String::from(message),
+        }
+    }
+}
+
+impl From<image::ImageError> for TextureError {
+    /// Convert an `image::ImageError` to a `TextureError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: image::ImageError) -> Self {
+        TextureError {
+            kind: String::from("image"),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// A two dimensional RGBA texture.
+pub struct Texture {
+    /// The texture, deleted when dropped.
+    id: GlObject,
+
+    /// The texture width in pixels.
+    width: u32,
+
+    /// The texture height in pixels.
+    height: u32,
+
+    /// How the GPU stores the pixels (`gl::RGBA8`, ...).
+    internal_format: GLenum,
+}
+
+impl Texture {
+    /// Create a texture from raw RGBA pixels.
+    ///
+    /// # Arguments
+    /// * `width` - The width of the texture in pixels.
+    /// * `height` - The height of the texture in pixels.
+    /// * `pixels` - The pixels, 4 bytes per pixel, starting from the bottom left corner.
+    pub fn from_pixels(width: u32, height: u32, pixels: &[u8]) -> Result<Self, TextureError> {
+        let size = width as usize * height as usize * 4;
+
+        if pixels.len() != size {
+            let msg = format!("Expected {} bytes of pixels but got {}", size, pixels.len());
+            return Err(TextureError::new("Size", &msg))
+        }
+
+        let id = GlObject::generate(GlObjectKind::Texture);
+        let internal_format = gl::RGBA8;
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, id.get_id());
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as GLint);
+
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                internal_format as GLint,
+                width as i32,
+                height as i32,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const gl::types::GLvoid
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        Ok(Self { id, width, height, internal_format })
+    }
+
+    /// Load a texture from an image file (png, jpeg, ...).
+    ///
+    /// # Arguments
+    /// * `path` - The path of the image.
+    pub fn from_file(path: &str) -> Result<Self, TextureError> {
+        let image = image::open(path)?.flipv().to_rgba8();
+        let (width, height) = image.dimensions();
+
+        Texture::from_pixels(width, height, &image.into_raw())
+    }
+
+    /// Just return the texture id.
+    pub fn get_id(&self) -> GLuint {
+        self.id.get_id()
+    }
+
+    /// The texture size in pixels.
+    pub fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// How the GPU stores the pixels (`gl::RGBA8`, ...).
+    pub fn get_internal_format(&self) -> GLenum {
+        self.internal_format
+    }
+
+    /// Set how the texture is sampled.
+    ///
+    /// # Arguments
+    /// * `filter` - The filter used for both minification and magnification.
+    pub fn set_filter(&self, filter: TextureFilter) {
+        let filter = match filter {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear  => gl::LINEAR,
+        };
+
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.get_id());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as GLint);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as GLint);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    /// Bind the texture to an image unit, for the `image2D` uniforms of
+    /// compute shaders. The image is accessed with the internal format of
+    /// the texture, the uniform format must match (`layout(rgba8, binding = n)`).
+    ///
+    /// # Arguments
+    /// * `unit` - The image unit.
+    /// * `access` - How the shader accesses the image.
+    pub fn bind_image(&self, unit: u32, access: ImageAccess) {
+        let access = match access {
+            ImageAccess::ReadOnly  => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        };
+
+        unsafe { gl::BindImageTexture(unit, self.get_id(), 0, gl::FALSE, 0, access, self.internal_format) };
+    }
+
+    /// Bind the texture to a texture unit.
+    ///
+    /// # Arguments
+    /// * `unit` - The texture unit (0 for `GL_TEXTURE0`, ...).
+    pub fn bind(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, self.get_id());
+        }
+    }
+}
+
+#[cfg(test)]
+impl Texture {
+    /// A texture without GL texture, for the tests that run without GL context.
+    ///
+    /// # Arguments
+    /// * `width` - The width of the texture in pixels.
+    /// * `height` - The height of the texture in pixels.
+    pub fn headless(width: u32, height: u32) -> Self {
+        Self { id: GlObject::null(GlObjectKind::Texture), width, height, internal_format: gl::RGBA8 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_pixels_of_another_size() {
+        // Checked before any GL call.
+        assert!(matches!(Texture::from_pixels(2, 2, &[0; 12]), Err(e) if e.kind == "Size"));
+
+        // 65536 * 65536 * 4 bytes doesn't fit in an `u32`.
+        assert!(matches!(Texture::from_pixels(65536, 65536, &[]), Err(e) if e.kind == "Size"));
+    }
+}
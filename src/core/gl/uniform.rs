@@ -0,0 +1,141 @@
+use cgmath::{
+    Matrix2,
+    Matrix3,
+    Matrix4,
+    Vector2,
+    Vector3,
+    Vector4,
+};
+use gl::types::{ GLenum, GLint, GLsizei };
+
+/// An active uniform of a linked shader program.
+#[derive(Clone, Debug)]
+pub struct UniformInfo {
+    /// The uniform name, without the `[0]` suffix of arrays.
+    pub name: String,
+
+    /// The GL type of the uniform (`gl::FLOAT_VEC2`, `gl::SAMPLER_2D`, ...).
+    pub gl_type: GLenum,
+
+    /// The amount of elements, greater than `1` for arrays.
+    pub size: i32,
+
+    /// The uniform location.
+    pub location: GLint,
+}
+
+/// A value that can be uploaded to a uniform with `Shader::set_uniform`.
+pub trait UniformType: Copy {
+    /// The GL type of the uniforms that accept this value.
+    const GL_TYPE: GLenum;
+
+    /// Upload values to the uniform of the program in use.
+    ///
+    /// # Arguments
+    /// * `location` - The uniform location.
+    /// * `values` - The values, more than one for arrays.
+    ///
+    /// # Safety
+    /// A GL context must be current with the program in use, and the uniform
+    /// at `location` must accept `values` (checked by `Shader::check_uniform`).
+    unsafe fn upload(location: GLint, values: &[Self]);
+}
+
+macro_rules! uniform_vectors {
+    ( $( $t:ty => $gl_type:ident, $func:ident, $elem:ty; )+ ) => { $(
+        impl UniformType for $t {
+            const GL_TYPE: GLenum = gl::$gl_type;
+
+            unsafe fn upload(location: GLint, values: &[Self]) {
+                gl::$func(location, values.len() as GLsizei, values.as_ptr() as *const $elem);
+            }
+        }
+    )+ };
+}
+
+macro_rules! uniform_matrices {
+    ( $( $t:ty => $gl_type:ident, $func:ident; )+ ) => { $(
+        impl UniformType for $t {
+            const GL_TYPE: GLenum = gl::$gl_type;
+
+            unsafe fn upload(location: GLint, values: &[Self]) {
+                gl::$func(location, values.len() as GLsizei, gl::FALSE, values.as_ptr() as *const f32);
+            }
+        }
+    )+ };
+}
+
+uniform_vectors! {
+    f32          => FLOAT,             Uniform1fv,  f32;
+    Vector2<f32> => FLOAT_VEC2,        Uniform2fv,  f32;
+    Vector3<f32> => FLOAT_VEC3,        Uniform3fv,  f32;
+    Vector4<f32> => FLOAT_VEC4,        Uniform4fv,  f32;
+    i32          => INT,               Uniform1iv,  i32;
+    Vector2<i32> => INT_VEC2,          Uniform2iv,  i32;
+    Vector3<i32> => INT_VEC3,          Uniform3iv,  i32;
+    Vector4<i32> => INT_VEC4,          Uniform4iv,  i32;
+    u32          => UNSIGNED_INT,      Uniform1uiv, u32;
+    Vector2<u32> => UNSIGNED_INT_VEC2, Uniform2uiv, u32;
+    Vector3<u32> => UNSIGNED_INT_VEC3, Uniform3uiv, u32;
+    Vector4<u32> => UNSIGNED_INT_VEC4, Uniform4uiv, u32;
+}
+
+uniform_matrices! {
+    Matrix2<f32> => FLOAT_MAT2, UniformMatrix2fv;
+    Matrix3<f32> => FLOAT_MAT3, UniformMatrix3fv;
+    Matrix4<f32> => FLOAT_MAT4, UniformMatrix4fv;
+}
+
+impl UniformType for bool {
+    const GL_TYPE: GLenum = gl::BOOL;
+
+    unsafe fn upload(location: GLint, values: &[Self]) {
+        let values = values.iter().map(|v| *v as i32).collect::<Vec<i32>>();
+        gl::Uniform1iv(location, values.len() as GLsizei, values.as_ptr());
+    }
+}
+
+/// `true` if the GL type is a sampler, samplers are set with an `i32` (the texture unit).
+fn is_sampler(gl_type: GLenum) -> bool {
+    matches!(gl_type,
+        gl::SAMPLER_1D | gl::SAMPLER_2D | gl::SAMPLER_3D | gl::SAMPLER_CUBE |
+        gl::SAMPLER_1D_ARRAY | gl::SAMPLER_2D_ARRAY | gl::SAMPLER_BUFFER |
+        gl::SAMPLER_1D_SHADOW | gl::SAMPLER_2D_SHADOW | gl::SAMPLER_CUBE_SHADOW |
+        gl::SAMPLER_2D_MULTISAMPLE | gl::INT_SAMPLER_2D | gl::UNSIGNED_INT_SAMPLER_2D)
+}
+
+/// `true` if a value of a GL type can be uploaded to a uniform.
+///
+/// # Arguments
+/// * `uniform_type` - The GL type of the uniform.
+/// * `value_type` - The GL type of the value.
+pub fn accepts(uniform_type: GLenum, value_type: GLenum) -> bool {
+    uniform_type == value_type || (value_type == gl::INT && is_sampler(uniform_type))
+}
+
+/// The GLSL name of a GL type, for error messages.
+pub fn glsl_type_name(gl_type: GLenum) -> &'static str {
+    match gl_type {
+        gl::FLOAT             => "float",
+        gl::FLOAT_VEC2        => "vec2",
+        gl::FLOAT_VEC3        => "vec3",
+        gl::FLOAT_VEC4        => "vec4",
+        gl::INT               => "int",
+        gl::INT_VEC2          => "ivec2",
+        gl::INT_VEC3          => "ivec3",
+        gl::INT_VEC4          => "ivec4",
+        gl::UNSIGNED_INT      => "uint",
+        gl::UNSIGNED_INT_VEC2 => "uvec2",
+        gl::UNSIGNED_INT_VEC3 => "uvec3",
+        gl::UNSIGNED_INT_VEC4 => "uvec4",
+        gl::BOOL              => "bool",
+        gl::BOOL_VEC2         => "bvec2",
+        gl::BOOL_VEC3         => "bvec3",
+        gl::BOOL_VEC4         => "bvec4",
+        gl::FLOAT_MAT2        => "mat2",
+        gl::FLOAT_MAT3        => "mat3",
+        gl::FLOAT_MAT4        => "mat4",
+        t if is_sampler(t)    => "sampler",
+        _                     => "unknown type",
+    }
+}
@@ -0,0 +1,462 @@
+use cgmath::{
+    Vector2,
+    Vector3,
+    Vector4,
+};
+use gl::types::{ GLenum, GLint, GLsizei };
+
+/// The type of the components of a vertex attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AttributeType {
+    Float,
+    Int,
+    UInt,
+    Short,
+    UShort,
+    Byte,
+    UByte,
+}
+
+impl AttributeType {
+    /// The GL enum of the type.
+    pub fn gl_enum(self) -> GLenum {
+        match self {
+            AttributeType::Float  => gl::FLOAT,
+            AttributeType::Int    => gl::INT,
+            AttributeType::UInt   => gl::UNSIGNED_INT,
+            AttributeType::Short  => gl::SHORT,
+            AttributeType::UShort => gl::UNSIGNED_SHORT,
+            AttributeType::Byte   => gl::BYTE,
+            AttributeType::UByte  => gl::UNSIGNED_BYTE,
+        }
+    }
+
+    /// The size of a component in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            AttributeType::Float | AttributeType::Int | AttributeType::UInt => 4,
+            AttributeType::Short | AttributeType::UShort => 2,
+            AttributeType::Byte | AttributeType::UByte => 1,
+        }
+    }
+}
+
+/// An attribute of a vertex: where it is in the vertex and where it goes in the shader.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VertexAttribute {
+    /// The attribute name, for error messages.
+    pub name: String,
+
+    /// The shader attribute location (`layout(location = n)`).
+    pub location: u32,
+
+    /// The type of the components.
+    pub attr_type: AttributeType,
+
+    /// The amount of components, from 1 to 4.
+    pub components: i32,
+
+    /// `true` if integer components are converted to `[0, 1]` (`[-1, 1]` if signed) floats.
+    /// Integer components that are not normalized are read by integer shader attributes (`ivec`, `uvec`).
+    pub normalized: bool,
+
+    /// The position of the attribute in the vertex, in bytes.
+    pub offset: usize,
+}
+
+impl VertexAttribute {
+    /// `true` if the attribute is read by an integer shader attribute.
+    pub fn is_integer(&self) -> bool {
+        self.attr_type != AttributeType::Float && !self.normalized
+    }
+}
+
+/// How the attributes of a vertex type are laid out in memory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VertexLayout {
+    /// The size of a vertex in bytes.
+    stride: usize,
+
+    /// The attributes.
+    attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Create new `VertexLayout` without attributes.
+    /// # Arguments
+    /// * `stride` - The size of a vertex in bytes.
+    pub fn new(stride: usize) -> Self {
+        Self {
+            stride,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Add an attribute.
+    /// # Arguments
+    /// * `attribute` - The attribute.
+    pub fn with_attribute(mut self, attribute: VertexAttribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// The size of a vertex in bytes.
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+
+    /// The attributes.
+    pub fn get_attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Get the attribute bound to a shader location.
+    /// # Arguments
+    /// * `location` - The shader attribute location.
+    pub fn get_attribute(&self, location: u32) -> Option<&VertexAttribute> {
+        self.attributes.iter().find(|a| a.location == location)
+    }
+
+    /// Check that the attributes fit in the vertex and use distinct locations.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, attribute) in self.attributes.iter().enumerate() {
+            if attribute.components < 1 || attribute.components > 4 {
+                return Err(format!("Vertex attribute '{}' has {} components, expected 1 to 4", attribute.name, attribute.components))
+            }
+
+            let end = attribute.offset + attribute.attr_type.size() * attribute.components as usize;
+
+            if end > self.stride {
+                return Err(format!("Vertex attribute '{}' ends at byte {} but a vertex is {} bytes", attribute.name, end, self.stride))
+            }
+
+            if let Some(other) = self.attributes[..i].iter().find(|a| a.location == attribute.location) {
+                return Err(format!("Vertex attributes '{}' and '{}' both use location {}", other.name, attribute.name, attribute.location))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the attribute pointers of the bound vertex array,
+    /// reading from the bound `gl::ARRAY_BUFFER`.
+    ///
+    /// # Safety
+    /// A GL context must be current with a vertex array and an array buffer
+    /// bound, and the layout must be valid (see `validate`) for the vertices
+    /// of the buffer, the GPU reads them with these offsets and this stride.
+    pub unsafe fn apply(&self) {
+        for attribute in self.attributes.iter() {
+            let offset = attribute.offset as *const gl::types::GLvoid;
+
+            if attribute.is_integer() {
+                gl::VertexAttribIPointer(attribute.location, attribute.components, attribute.attr_type.gl_enum(), self.stride as GLsizei, offset);
+            } else {
+                gl::VertexAttribPointer(attribute.location, attribute.components, attribute.attr_type.gl_enum(), attribute.normalized as u8, self.stride as GLsizei, offset);
+            }
+
+            gl::EnableVertexAttribArray(attribute.location);
+        }
+    }
+}
+
+/// An active vertex attribute of a linked shader program.
+#[derive(Clone, Debug)]
+pub struct AttributeInfo {
+    /// The attribute name.
+    pub name: String,
+
+    /// The GL type of the attribute (`gl::FLOAT_VEC2`, `gl::INT`, ...).
+    pub gl_type: GLenum,
+
+    /// The attribute location.
+    pub location: GLint,
+}
+
+impl AttributeInfo {
+    /// The amount of components and `true` if the shader reads integers,
+    /// `None` for the types a `VertexLayout` can't feed (matrices, doubles).
+    pub fn format(&self) -> Option<(i32, bool)> {
+        match self.gl_type {
+            gl::FLOAT                => Some((1, false)),
+            gl::FLOAT_VEC2           => Some((2, false)),
+            gl::FLOAT_VEC3           => Some((3, false)),
+            gl::FLOAT_VEC4           => Some((4, false)),
+            gl::INT | gl::UNSIGNED_INT                     => Some((1, true)),
+            gl::INT_VEC2 | gl::UNSIGNED_INT_VEC2           => Some((2, true)),
+            gl::INT_VEC3 | gl::UNSIGNED_INT_VEC3           => Some((3, true)),
+            gl::INT_VEC4 | gl::UNSIGNED_INT_VEC4           => Some((4, true)),
+            _ => None,
+        }
+    }
+}
+
+/// Check that a vertex layout provides every attribute a shader reads.
+///
+/// # Arguments
+/// * `layout` - The vertex layout.
+/// * `attributes` - The active attributes of the shader.
+pub fn check_compatibility<'a>(layout: &VertexLayout, attributes: impl IntoIterator<Item = &'a AttributeInfo>) -> Result<(), String> {
+    for info in attributes {
+        let attribute = match layout.get_attribute(info.location as u32) {
+            Some(attribute) => attribute,
+            None => return Err(format!("The vertex layout has no attribute at location {} for shader attribute '{}'", info.location, info.name)),
+        };
+
+        let (components, integer) = match info.format() {
+            Some(format) => format,
+            None => return Err(format!("Shader attribute '{}' has a type vertex layouts don't support", info.name)),
+        };
+
+        if attribute.components != components || attribute.is_integer() != integer {
+            let expected = |n: i32, int: bool| format!("{} {} component(s)", n, if int { "integer" } else { "float" });
+
+            return Err(format!(
+                "Vertex attribute '{}' has {} but shader attribute '{}' reads {}",
+                attribute.name,
+                expected(attribute.components, attribute.is_integer()),
+                info.name,
+                expected(components, integer),
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// A vertex type that can be stored in a mesh.
+///
+/// Implement it with `impl_vertex!` for `#[repr(C)]` structs:
+///
+/// ```ignore
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// pub struct ColorVertex {
+///     pub pos: Vector2<f32>,
+///     pub color: [u8; 4],
+/// }
+///
+/// impl_vertex!(ColorVertex { pos => 0, color => 2 });
+/// ```
+pub trait Vertex: Copy + 'static {
+    /// How the attributes are laid out.
+    fn layout() -> VertexLayout;
+}
+
+/// A type that can be a field of a vertex.
+pub trait AttributeFormat {
+    /// The type of the components.
+    const TYPE: AttributeType;
+
+    /// The amount of components.
+    const COMPONENTS: i32;
+
+    /// `true` if the components are normalized by default (bytes are colors).
+    const NORMALIZED: bool = false;
+}
+
+macro_rules! attribute_formats {
+    ( $( $t:ty => $attr_type:ident, $components:expr, $normalized:expr; )+ ) => { $(
+        impl AttributeFormat for $t {
+            const TYPE: AttributeType = AttributeType::$attr_type;
+            const COMPONENTS: i32 = $components;
+            const NORMALIZED: bool = $normalized;
+        }
+    )+ };
+}
+
+attribute_formats! {
+    f32          => Float, 1, false;
+    Vector2<f32> => Float, 2, false;
+    Vector3<f32> => Float, 3, false;
+    Vector4<f32> => Float, 4, false;
+    [f32; 2]     => Float, 2, false;
+    [f32; 3]     => Float, 3, false;
+    [f32; 4]     => Float, 4, false;
+    i32          => Int,   1, false;
+    Vector2<i32> => Int,   2, false;
+    Vector3<i32> => Int,   3, false;
+    Vector4<i32> => Int,   4, false;
+    u32          => UInt,  1, false;
+    Vector2<u32> => UInt,  2, false;
+    Vector3<u32> => UInt,  3, false;
+    Vector4<u32> => UInt,  4, false;
+    [u16; 2]     => UShort, 2, true;
+    [u8; 4]      => UByte, 4, true;
+}
+
+/// Build the attribute of a vertex field, used by `impl_vertex!`.
+///
+/// # Arguments
+/// * `name` - The field name.
+/// * `location` - The shader attribute location.
+/// * `offset` - The position of the field in the vertex, in bytes.
+/// * `_field` - A pointer to the field, only used to infer its type.
+pub fn attribute_of<F: AttributeFormat>(name: &str, location: u32, offset: usize, _field: *const F) -> VertexAttribute {
+    VertexAttribute {
+        name: String::from(name),
+        location,
+        attr_type: F::TYPE,
+        components: F::COMPONENTS,
+        normalized: F::NORMALIZED,
+        offset,
+    }
+}
+
+/// Implement `Vertex` for a `#[repr(C)]` struct, binding fields to shader locations.
+///
+/// The attribute type of each field comes from its `AttributeFormat` implementation.
+#[macro_export]
+macro_rules! impl_vertex {
+    ( $t:ty { $( $field:ident => $location:expr ),+ $(,)? } ) => {
+        impl $crate::core::gl::vertex::Vertex for $t {
+            fn layout() -> $crate::core::gl::vertex::VertexLayout {
+                let vertex = std::mem::MaybeUninit::<$t>::uninit();
+                let base = vertex.as_ptr();
+
+                $crate::core::gl::vertex::VertexLayout::new(std::mem::size_of::<$t>())
+                $(
+                    .with_attribute({
+                        // SAFETY: only the address of the field is taken, the vertex is never read.
+                        let field = unsafe { std::ptr::addr_of!((*base).$field) };
+                        let offset = field as usize - base as usize;
+                        $crate::core::gl::vertex::attribute_of(stringify!($field), $location, offset, field)
+                    })
+                )+
+            }
+        }
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct TestVertex {
+        pos: Vector2<f32>,
+        color: [u8; 4],
+        id: u32,
+        weight: f32,
+    }
+
+    crate::impl_vertex!(TestVertex { pos => 0, color => 1, id => 2, weight => 3 });
+
+    fn attribute(name: &str, location: u32, attr_type: AttributeType, components: i32, offset: usize) -> VertexAttribute {
+        VertexAttribute {
+            name: String::from(name),
+            location,
+            attr_type,
+            components,
+            normalized: false,
+            offset,
+        }
+    }
+
+    fn info(name: &str, location: GLint, gl_type: GLenum) -> AttributeInfo {
+        AttributeInfo {
+            name: String::from(name),
+            gl_type,
+            location,
+        }
+    }
+
+    #[test]
+    fn impl_vertex_offsets() {
+        let layout = TestVertex::layout();
+        let attributes = layout.get_attributes();
+
+        assert_eq!(layout.get_stride(), std::mem::size_of::<TestVertex>());
+        assert_eq!(attributes.len(), 4);
+
+        let offsets: Vec<usize> = attributes.iter().map(|a| a.offset).collect();
+        assert_eq!(offsets, vec![0, 8, 12, 16]);
+
+        let pos = layout.get_attribute(0).unwrap();
+        assert_eq!((pos.attr_type, pos.components, pos.normalized), (AttributeType::Float, 2, false));
+
+        let color = layout.get_attribute(1).unwrap();
+        assert_eq!((color.attr_type, color.components, color.normalized), (AttributeType::UByte, 4, true));
+        assert!(!color.is_integer());
+
+        let id = layout.get_attribute(2).unwrap();
+        assert_eq!(id.name, "id");
+        assert!(id.is_integer());
+
+        assert!(layout.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_bad_components() {
+        let layout = VertexLayout::new(16).with_attribute(attribute("a", 0, AttributeType::Float, 5, 0));
+        assert!(layout.validate().is_err());
+
+        let layout = VertexLayout::new(16).with_attribute(attribute("a", 0, AttributeType::Float, 0, 0));
+        assert!(layout.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_attribute_out_of_vertex() {
+        let layout = VertexLayout::new(12).with_attribute(attribute("a", 0, AttributeType::Float, 2, 8));
+        assert!(layout.validate().is_err());
+
+        let layout = VertexLayout::new(16).with_attribute(attribute("a", 0, AttributeType::Float, 2, 8));
+        assert!(layout.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_shared_locations() {
+        let layout = VertexLayout::new(16)
+            .with_attribute(attribute("a", 0, AttributeType::Float, 2, 0))
+            .with_attribute(attribute("b", 0, AttributeType::Float, 2, 8));
+
+        let error = layout.validate().unwrap_err();
+        assert!(error.contains("'a'") && error.contains("'b'"));
+    }
+
+    #[test]
+    fn compatible_layout() {
+        let layout = TestVertex::layout();
+        let attributes = [
+            info("pos", 0, gl::FLOAT_VEC2),
+            info("color", 1, gl::FLOAT_VEC4),
+            info("id", 2, gl::UNSIGNED_INT),
+        ];
+
+        assert!(check_compatibility(&layout, attributes.iter()).is_ok());
+    }
+
+    #[test]
+    fn missing_location_is_incompatible() {
+        let layout = TestVertex::layout();
+        let attributes = [info("uv", 7, gl::FLOAT_VEC2)];
+
+        assert!(check_compatibility(&layout, attributes.iter()).is_err());
+    }
+
+    #[test]
+    fn component_mismatch_is_incompatible() {
+        let layout = TestVertex::layout();
+
+        let attributes = [info("pos", 0, gl::FLOAT_VEC3)];
+        assert!(check_compatibility(&layout, attributes.iter()).is_err());
+
+        // Normalized bytes are floats for the shader, not integers.
+        let attributes = [info("color", 1, gl::INT_VEC4)];
+        assert!(check_compatibility(&layout, attributes.iter()).is_err());
+
+        // Integers that are not normalized can't feed a float attribute.
+        let attributes = [info("id", 2, gl::FLOAT)];
+        assert!(check_compatibility(&layout, attributes.iter()).is_err());
+    }
+
+    #[test]
+    fn unsupported_type_is_incompatible() {
+        let layout = TestVertex::layout();
+        let attributes = [info("weight", 3, gl::FLOAT_MAT2)];
+
+        assert!(check_compatibility(&layout, attributes.iter()).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+
+use std::{io::Write, path::{Path, PathBuf}};
+
+use glfw::{Action, Modifiers, MouseButton};
+
+use crate::core::{
+    keys::ALL_KEYS,
+    platform_event::PlatformEvent,
+};
+
+/// An input recording error.
+#[derive(Debug)]
+pub struct RecordingError {
+    /// Kind of error.
+    pub kind: String,
+
+    /// The error description.
+    pub message: String,
+}
+
+impl RecordingError {
+    /// Create new recording error.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of error.
+    /// * `message` - The error description.
+    pub fn new(kind: &str, message: &str) -> RecordingError {
+        RecordingError {
+            kind: String::from(kind),
+            message: String::from(message),
+        }
+    }
+}
+
+impl std::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} error: {}", self.kind, self.message)
+    }
+}
+
+impl From<std::io::Error> for RecordingError {
+    /// Convert an `std::io::Error` to a `RecordingError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: std::io::Error) -> Self {
+        RecordingError::new("io", &error.to_string())
+    }
+}
+
+/// The input of a single frame.
+#[derive(Clone)]
+pub struct RecordedFrame {
+    /// The time (in seconds) elapsed since the previous frame.
+    pub delta: f32,
+
+    /// The platform events of the frame.
+    pub events: Vec<PlatformEvent>,
+}
+
+/// A sequence of recorded frames.
+///
+/// Recordings are saved as text, one line per frame
+/// (`frame <delta>`) followed by one line per event.
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        InputRecording::new()
+    }
+}
+
+impl InputRecording {
+    /// Create new empty `InputRecording`.
+    pub fn new() -> Self {
+        Self {
+            frames: Vec::new(),
+        }
+    }
+
+    /// Load a recording from a file.
+    /// # Arguments
+    /// * `path` - The path of the recording.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        InputRecording::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save the recording to a file.
+    /// # Arguments
+    /// * `path` - The path of the recording.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordingError> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        for frame in self.frames.iter() {
+            write_frame(&mut file, frame)?;
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Parse a recording from its text representation.
+    /// # Arguments
+    /// * `src` - The recording text.
+    pub fn parse(src: &str) -> Result<Self, RecordingError> {
+        let mut frames = Vec::<RecordedFrame>::new();
+
+        for (i, line) in src.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let invalid = || RecordingError::new("Parse", &format!("Invalid line {}: '{}'", i + 1, line));
+
+            if let Some(delta) = line.strip_prefix("frame ") {
+                let delta = delta.trim().parse::<f32>().map_err(|_| invalid())?;
+                frames.push(RecordedFrame { delta, events: Vec::new() });
+                continue
+            }
+
+            let event = parse_event(line).ok_or_else(invalid)?;
+
+            match frames.last_mut() {
+                Some(frame) => frame.events.push(event),
+                None => return Err(invalid()),
+            }
+        }
+
+        Ok(Self { frames })
+    }
+}
+
+/// Write a frame in its text representation.
+/// # Arguments
+/// * `out` - Where the frame is written.
+/// * `frame` - The frame to write.
+pub fn write_frame<W: Write>(out: &mut W, frame: &RecordedFrame) -> std::io::Result<()> {
+    writeln!(out, "frame {}", frame.delta)?;
+
+    for event in frame.events.iter() {
+        writeln!(out, "{}", format_event(event))?;
+    }
+
+    Ok(())
+}
+
+fn action_code(action: Action) -> i32 {
+    match action {
+        Action::Release => 0,
+        Action::Press   => 1,
+        Action::Repeat  => 2,
+    }
+}
+
+fn action_from_code(code: i32) -> Option<Action> {
+    match code {
+        0 => Some(Action::Release),
+        1 => Some(Action::Press),
+        2 => Some(Action::Repeat),
+        _ => None,
+    }
+}
+
+/// Convert an event into a single line of text.
+fn format_event(event: &PlatformEvent) -> String {
+    match event {
+        PlatformEvent::Key { key, scancode, action, mods } => format!("key {} {} {} {}", *key as i32, scancode, action_code(*action), mods.bits()),
+        PlatformEvent::MouseButton { button, action, mods } => format!("button {} {} {}", *button as i32, action_code(*action), mods.bits()),
+        PlatformEvent::CursorMoved { x, y } => format!("cursor {} {}", x, y),
+        PlatformEvent::CursorEntered(entered) => format!("enter {}", *entered as i32),
+        PlatformEvent::Scroll { x, y } => format!("scroll {} {}", x, y),
+        PlatformEvent::Char(c) => format!("char {}", *c as u32),
+        PlatformEvent::Moved { x, y } => format!("moved {} {}", x, y),
+        PlatformEvent::Resized { width, height } => format!("resized {} {}", width, height),
+        PlatformEvent::FramebufferResized { width, height } => format!("framebuffer {} {}", width, height),
+        PlatformEvent::ScaleChanged { x, y } => format!("scale {} {}", x, y),
+        PlatformEvent::Focused(focused) => format!("focus {}", *focused as i32),
+        PlatformEvent::Iconified(iconified) => format!("iconify {}", *iconified as i32),
+        PlatformEvent::Maximized(maximized) => format!("maximize {}", *maximized as i32),
+        PlatformEvent::CloseRequested => String::from("close"),
+        PlatformEvent::FilesDropped(paths) => {
+            // A JSON array of strings, the paths can contain any character.
+            let paths = paths.iter().map(|p| p.to_string_lossy().into_owned()).collect::<Vec<String>>();
+            format!("drop {}", serde_json::to_string(&paths).unwrap())
+        },
+    }
+}
+
+/// Convert a line of text into an event.
+fn parse_event(line: &str) -> Option<PlatformEvent> {
+    if let Some(paths) = line.strip_prefix("drop ") {
+        let paths = serde_json::from_str::<Vec<String>>(paths).ok()?;
+        return Some(PlatformEvent::FilesDropped(paths.into_iter().map(PathBuf::from).collect()))
+    }
+
+    let mut parts = line.split_whitespace();
+    let name = parts.next()?;
+    let args = parts.collect::<Vec<&str>>();
+
+    let int = |i: usize| args.get(i)?.parse::<i32>().ok();
+    let uint = |i: usize| args.get(i)?.parse::<u32>().ok();
+    let float = |i: usize| args.get(i)?.parse::<f64>().ok();
+    let float32 = |i: usize| args.get(i)?.parse::<f32>().ok();
+    let flag = |i: usize| Some(int(i)? != 0);
+
+    Some(match name {
+        "key" => {
+            let code = int(0)?;
+
+            PlatformEvent::Key {
+                key: *ALL_KEYS.iter().find(|k| **k as i32 == code)?,
+                scancode: int(1)?,
+                action: action_from_code(int(2)?)?,
+                mods: Modifiers::from_bits_truncate(int(3)?),
+            }
+        },
+        "button" => PlatformEvent::MouseButton {
+            button: MouseButton::from_i32(int(0)?)?,
+            action: action_from_code(int(1)?)?,
+            mods: Modifiers::from_bits_truncate(int(2)?),
+        },
+        "cursor" => PlatformEvent::CursorMoved { x: float(0)?, y: float(1)? },
+        "enter" => PlatformEvent::CursorEntered(flag(0)?),
+        "scroll" => PlatformEvent::Scroll { x: float(0)?, y: float(1)? },
+        "char" => PlatformEvent::Char(std::char::from_u32(uint(0)?)?),
+        "moved" => PlatformEvent::Moved { x: int(0)?, y: int(1)? },
+        "resized" => PlatformEvent::Resized { width: uint(0)?, height: uint(1)? },
+        "framebuffer" => PlatformEvent::FramebufferResized { width: uint(0)?, height: uint(1)? },
+        "scale" => PlatformEvent::ScaleChanged { x: float32(0)?, y: float32(1)? },
+        "focus" => PlatformEvent::Focused(flag(0)?),
+        "iconify" => PlatformEvent::Iconified(flag(0)?),
+        "maximize" => PlatformEvent::Maximized(flag(0)?),
+        "close" => PlatformEvent::CloseRequested,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let frame = RecordedFrame {
+            delta: 0.016,
+            events: vec![
+                PlatformEvent::Key { key: ALL_KEYS[0], scancode: 38, action: Action::Press, mods: Modifiers::Shift },
+                PlatformEvent::MouseButton { button: MouseButton::Button2, action: Action::Release, mods: Modifiers::empty() },
+                PlatformEvent::CursorMoved { x: 12.5, y: -3.0 },
+                PlatformEvent::Char('é'),
+                PlatformEvent::FramebufferResized { width: 800, height: 600 },
+                PlatformEvent::Focused(false),
+                PlatformEvent::CloseRequested,
+                PlatformEvent::FilesDropped(vec![PathBuf::from("a\tb.png"), PathBuf::from("C:\\dir\\\"c\".png"), PathBuf::from("new\nline")]),
+            ],
+        };
+
+        let mut out = Vec::new();
+        write_frame(&mut out, &frame).unwrap();
+        write_frame(&mut out, &RecordedFrame { delta: 0.5, events: Vec::new() }).unwrap();
+
+        let recording = InputRecording::parse(&String::from_utf8(out).unwrap()).unwrap();
+
+        assert_eq!(recording.frames.len(), 2);
+        assert_eq!(recording.frames[0].delta, 0.016);
+        assert_eq!(recording.frames[0].events, frame.events);
+        assert!(recording.frames[1].events.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert!(InputRecording::parse("cursor 1 2\n").is_err());
+        assert!(InputRecording::parse("frame 0.1\nkey a\n").is_err());
+        assert!(InputRecording::parse("frame 0.1\ndrop [1, 2]\n").is_err());
+    }
+}
@@ -81,10 +81,11 @@ impl ComponentsManager {
         let type_id = TypeId::of::<T>();
         
         match self.components_arrays_id.get(&type_id) {
-            Some(id) => {
+            Some(id) if self.signatures[*entity].test(*id) => {
                 self.signatures[*entity].set(*id, false);
                 self.components_arrays[*id].rem_component(&entity);
             },
+            Some(_) => (),
             None => ()
         };
     }
@@ -94,8 +95,14 @@ impl ComponentsManager {
     /// # Arguments
     /// * `entity` - The entity that has the component we want to retrieve.
     pub fn get_component<T: Any>(&self, entity: &Entity) -> Option<&T> {
-        let key = TypeId::of::<T>();
-        Some(self.components_arrays[*self.components_arrays_id.get(&key)?].get_component(entity)?.downcast_ref::<T>()?)
+        let id = *self.components_arrays_id.get(&TypeId::of::<T>())?;
+
+        // Component arrays don't know which entities own a component, the signature does.
+        if !self.signatures[*entity].test(id) {
+            return None
+        }
+
+        Some(self.components_arrays[id].get_component(entity)?.downcast_ref::<T>()?)
     }
 
     /// Get mutable reference of an entity component.
@@ -103,14 +110,19 @@ impl ComponentsManager {
     /// # Arguments
     /// * `entity` - The entity that has the component we want to retrieve.
     pub fn get_component_mut<T: Any>(&mut self, entity: &Entity) -> Option<&mut T> {
-        let key = TypeId::of::<T>();
-        Some(self.components_arrays[*self.components_arrays_id.get(&key)?].get_component_mut(entity)?.downcast_mut::<T>()?)
+        let id = *self.components_arrays_id.get(&TypeId::of::<T>())?;
+
+        if !self.signatures[*entity].test(id) {
+            return None
+        }
+
+        Some(self.components_arrays[id].get_component_mut(entity)?.downcast_mut::<T>()?)
     }
 
     /// Retrieve a reference to a list of entities that have a certain type id.
     /// # Arguments
-    /// * `type_id` - 
-    pub fn get_entities_with_component(&self, type_id: &TypeId) -> Option<&Vec<Entity>> {
+    /// * `type_id` - The type id of the component.
+    pub fn get_entities_with_component(&self, type_id: &TypeId) -> Option<&[Entity]> {
         if let Some(id) = self.components_arrays_id.get(&type_id) {
             Some(self.components_arrays[*id].get_entities())
         } else { 
@@ -1,5 +1,5 @@
 
-use crate::{components::smc::SystemManagerComponent, core::world::{EntityComponentManager}};
+use crate::{components::smc::{AppExit, SystemManagerComponent}, core::world::{EntityComponentManager}};
 use crate::core::{
     sys::Sys,
     entity::Entity,
@@ -31,7 +31,7 @@ impl SystemManager {
     /// * `world` - The world state.
     pub fn init(&mut self, world: &mut EntityComponentManager) {
         self.smc_entity = world.create_entity().unwrap();
-        world.add_component(&self.smc_entity, SystemManagerComponent { shutdown: false });
+        world.add_component(&self.smc_entity, SystemManagerComponent { shutdown: false, exit_code: 0 });
 
         for sys in self.systems.iter() { 
             sys.on_start(world) 
@@ -49,6 +49,30 @@ impl SystemManager {
             sys.on_update(world) 
         }
 
+        let exit = world.read_events::<AppExit>().first().copied();
+        world.clear_events::<AppExit>();
+
+        if let Some(AppExit(code)) = exit {
+            let smc = world.get_component_mut::<SystemManagerComponent>(&self.smc_entity).unwrap();
+            smc.shutdown = true;
+            smc.exit_code = code;
+        }
+
         true
     }
+
+    /// Shut down all system, in the reverse order of registration.
+    ///
+    /// Returns the exit code.
+    /// * `world` - The world state.
+    pub fn shutdown(&mut self, world: &mut EntityComponentManager) -> i32 {
+        for sys in self.systems.iter().rev() {
+            sys.on_shutdown(world)
+        }
+
+        match world.get_component::<SystemManagerComponent>(&self.smc_entity) {
+            Some(smc) => smc.exit_code,
+            None => 0,
+        }
+    }
 }
\ No newline at end of file
@@ -3,4 +3,14 @@ pub mod component_array;
 pub mod sys;
 pub mod gl;
 pub mod managers;
-pub mod world;
\ No newline at end of file
+pub mod world;
+pub mod rect;
+pub mod atlas;
+pub mod sprite_sheet;
+pub mod events;
+pub mod platform_event;
+pub mod keys;
+pub mod input_record;
+pub mod shapes;
+#[cfg(test)]
+pub mod temp_dir;
@@ -0,0 +1,85 @@
+
+use std::path::PathBuf;
+
+use glfw::{Action, Key, Modifiers, MouseButton, Scancode};
+
+/// A window or input event, converted from a GLFW
+/// event by the platform event pump (`WindowSys`).
+///
+/// All the events of a frame are readable with
+/// `EntityComponentManager::read_events::<PlatformEvent>()`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PlatformEvent {
+    /// A keyboard key was pressed, repeated or released.
+    Key { key: Key, scancode: Scancode, action: Action, mods: Modifiers },
+
+    /// A mouse button was pressed or released.
+    MouseButton { button: MouseButton, action: Action, mods: Modifiers },
+
+    /// The cursor moved, in screen coordinates from the top left corner of the window.
+    CursorMoved { x: f64, y: f64 },
+
+    /// The cursor entered (`true`) or left (`false`) the window.
+    CursorEntered(bool),
+
+    /// The mouse wheel or touchpad was scrolled.
+    Scroll { x: f64, y: f64 },
+
+    /// A unicode character was typed.
+    Char(char),
+
+    /// The window was moved, in screen coordinates.
+    Moved { x: i32, y: i32 },
+
+    /// The window was resized, in screen coordinates.
+    Resized { width: u32, height: u32 },
+
+    /// The framebuffer was resized, in pixels.
+    FramebufferResized { width: u32, height: u32 },
+
+    /// The content scale (DPI scale) of the window changed.
+    ScaleChanged { x: f32, y: f32 },
+
+    /// The window gained (`true`) or lost (`false`) the focus.
+    Focused(bool),
+
+    /// The window was iconified (`true`) or restored (`false`).
+    Iconified(bool),
+
+    /// The window was maximized (`true`) or restored (`false`).
+    Maximized(bool),
+
+    /// The user asked to close the window.
+    CloseRequested,
+
+    /// Files were dropped on the window.
+    FilesDropped(Vec<PathBuf>),
+}
+
+impl PlatformEvent {
+    /// Convert a GLFW event into a `PlatformEvent`.
+    ///
+    /// Returns `None` for the events that the engine doesn't handle.
+    /// # Arguments
+    /// * `event` - The GLFW event.
+    pub fn from_glfw(event: glfw::WindowEvent) -> Option<Self> {
+        Some(match event {
+            glfw::WindowEvent::Key(key, scancode, action, mods) => PlatformEvent::Key { key, scancode, action, mods },
+            glfw::WindowEvent::MouseButton(button, action, mods) => PlatformEvent::MouseButton { button, action, mods },
+            glfw::WindowEvent::CursorPos(x, y) => PlatformEvent::CursorMoved { x, y },
+            glfw::WindowEvent::CursorEnter(entered) => PlatformEvent::CursorEntered(entered),
+            glfw::WindowEvent::Scroll(x, y) => PlatformEvent::Scroll { x, y },
+            glfw::WindowEvent::Char(c) => PlatformEvent::Char(c),
+            glfw::WindowEvent::Pos(x, y) => PlatformEvent::Moved { x, y },
+            glfw::WindowEvent::Size(w, h) => PlatformEvent::Resized { width: w.max(0) as u32, height: h.max(0) as u32 },
+            glfw::WindowEvent::FramebufferSize(w, h) => PlatformEvent::FramebufferResized { width: w.max(0) as u32, height: h.max(0) as u32 },
+            glfw::WindowEvent::ContentScale(x, y) => PlatformEvent::ScaleChanged { x, y },
+            glfw::WindowEvent::Focus(focused) => PlatformEvent::Focused(focused),
+            glfw::WindowEvent::Iconify(iconified) => PlatformEvent::Iconified(iconified),
+            glfw::WindowEvent::Maximize(maximized) => PlatformEvent::Maximized(maximized),
+            glfw::WindowEvent::Close => PlatformEvent::CloseRequested,
+            glfw::WindowEvent::FileDrop(paths) => PlatformEvent::FilesDropped(paths),
+            _ => return None,
+        })
+    }
+}
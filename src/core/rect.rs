@@ -0,0 +1,47 @@
+
+use cgmath::Vector2;
+
+/// An axis aligned rectangle.
+#[derive(Clone, Copy)]
+pub struct Rect {
+    /// The left edge of the rectangle.
+    pub x: f32,
+
+    /// The bottom edge of the rectangle.
+    pub y: f32,
+
+    /// The width of the rectangle.
+    pub w: f32,
+
+    /// The height of the rectangle.
+    pub h: f32,
+}
+
+impl Rect {
+    /// Create new `Rect`
+    /// # Arguments
+    /// * `x` - The left edge of the rectangle.
+    /// * `y` - The bottom edge of the rectangle.
+    /// * `w` - The width of the rectangle.
+    /// * `h` - The height of the rectangle.
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// The bottom left corner of the rectangle.
+    pub fn min(&self) -> Vector2<f32> {
+        Vector2::new(self.x, self.y)
+    }
+
+    /// The top right corner of the rectangle.
+    pub fn max(&self) -> Vector2<f32> {
+        Vector2::new(self.x + self.w, self.y + self.h)
+    }
+
+    /// Check if a point is inside the rectangle.
+    /// # Arguments
+    /// * `point` - The point to check.
+    pub fn contains(&self, point: Vector2<f32>) -> bool {
+        point.x >= self.x && point.x <= self.x + self.w && point.y >= self.y && point.y <= self.y + self.h
+    }
+}
@@ -0,0 +1,583 @@
+use std::{f32::consts::PI, rc::Rc};
+
+use cgmath::{
+    InnerSpace,
+    Vector2,
+};
+
+use crate::core::gl::material::Material;
+
+use crate::components::mesh_2d::{
+    Indices,
+    Mesh2D,
+    MeshError,
+    Vertex2D,
+};
+
+/// The triangles of a 2D shape, ready to be uploaded as a `Mesh2D`.
+///
+/// Their triangles are counter clockwise, the front face set by `BeforeRenderSys`.
+/// The shapes built from a size are centered on the origin, `polygon` keeps
+/// the coordinates of its points.
+/// The normals point outward on the outline (they are zero inside the shape)
+/// and the uv coordinates map the bounding box of the shape to `[0, 1]`.
+pub struct Shape {
+    /// The vertices.
+    pub vertices: Vec<Vertex2D>,
+
+    /// The triangles, three indices in `vertices` each.
+    pub indices: Vec<u32>,
+}
+
+impl Shape {
+    /// Build a shape, the uv coordinates are computed from the bounding box.
+    /// # Arguments
+    /// * `positions` - The vertex positions.
+    /// * `normals` - The vertex normals.
+    /// * `indices` - The triangles.
+    fn new(positions: Vec<Vector2<f32>>, normals: Vec<Vector2<f32>>, indices: Vec<u32>) -> Self {
+        let min = positions.iter().fold(Vector2::new(f32::MAX, f32::MAX), |m, p| Vector2::new(m.x.min(p.x), m.y.min(p.y)));
+        let max = positions.iter().fold(Vector2::new(f32::MIN, f32::MIN), |m, p| Vector2::new(m.x.max(p.x), m.y.max(p.y)));
+        let size = max - min;
+
+        let uv = |p: Vector2<f32>| Vector2::new(
+            if size.x > 0.0 { (p.x - min.x) / size.x } else { 0.0 },
+            if size.y > 0.0 { (p.y - min.y) / size.y } else { 0.0 },
+        );
+
+        Self {
+            vertices: positions.iter().zip(normals).map(|(p, n)| Vertex2D::new(*p, n, uv(*p))).collect(),
+            indices,
+        }
+    }
+
+    /// The amount of triangles.
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Upload the shape as a mesh.
+    /// # Arguments
+    /// * `material` - How the mesh looks.
+    /// * `is_dynamic` - `true` if the mesh will be updated often.
+    pub fn into_mesh(self, material: Rc<Material>, is_dynamic: bool) -> Result<Mesh2D, MeshError> {
+        Mesh2D::new(self.vertices, Indices::compact(self.indices), material, is_dynamic)
+    }
+}
+
+/// The tolerance of the geometric tests, relative to the size of the shape:
+/// points closer than `TOLERANCE * size` are the same point.
+pub(crate) const TOLERANCE: f32 = 1e-5;
+
+/// The largest side of the bounding box of points, the size the tolerance is relative to.
+pub(crate) fn extent(points: &[Vector2<f32>]) -> f32 {
+    let min = points.iter().fold(Vector2::new(f32::MAX, f32::MAX), |m, p| Vector2::new(m.x.min(p.x), m.y.min(p.y)));
+    let max = points.iter().fold(Vector2::new(f32::MIN, f32::MIN), |m, p| Vector2::new(m.x.max(p.x), m.y.max(p.y)));
+    (max.x - min.x).max(max.y - min.y).max(0.0)
+}
+
+/// Twice the signed area of a polygon, positive if it is counter clockwise.
+fn double_area(points: &[Vector2<f32>]) -> f32 {
+    (0..points.len()).map(|i| {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        a.x * b.y - b.x * a.y
+    }).sum()
+}
+
+/// The z component of the cross product of `b - a` and `c - a`, positive if `a, b, c` turn left.
+fn cross(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Remove the consecutive duplicated points of a closed outline.
+fn dedup(points: Vec<Vector2<f32>>) -> Vec<Vector2<f32>> {
+    let epsilon = (extent(&points) * TOLERANCE).powi(2);
+    let mut out: Vec<Vector2<f32>> = Vec::with_capacity(points.len());
+
+    for p in points {
+        if out.last().is_none_or(|l| (p - *l).magnitude2() > epsilon) {
+            out.push(p);
+        }
+    }
+
+    while out.len() > 1 && (out[0] - out[out.len() - 1]).magnitude2() <= epsilon {
+        out.pop();
+    }
+
+    out
+}
+
+/// The outward normals of the points of a counter clockwise outline,
+/// the bisector of the normals of the two edges of each point.
+fn outline_normals(points: &[Vector2<f32>]) -> Vec<Vector2<f32>> {
+    let n = points.len();
+
+    let edge_normal = |i: usize| {
+        let d = points[(i + 1) % n] - points[i];
+        let normal = Vector2::new(d.y, -d.x);
+        if normal.magnitude2() > 0.0 { normal.normalize() } else { normal }
+    };
+
+    (0..n).map(|i| {
+        let normal = edge_normal((i + n - 1) % n) + edge_normal(i);
+        if normal.magnitude2() > 0.0 { normal.normalize() } else { normal }
+    }).collect()
+}
+
+/// Triangulate a counter clockwise outline that is star shaped
+/// around its center, with a fan of triangles around the center.
+fn fan(outline: Vec<Vector2<f32>>) -> Shape {
+    let outline = dedup(outline);
+    let n = outline.len() as u32;
+    let center = outline.iter().fold(Vector2::new(0.0, 0.0), |c, p| c + p) / n.max(1) as f32;
+
+    let mut normals = vec![Vector2::new(0.0, 0.0)];
+    normals.extend(outline_normals(&outline));
+
+    let mut positions = vec![center];
+    positions.extend(outline);
+
+    let indices = (0..n).flat_map(|i| [0, i + 1, (i + 1) % n + 1]).collect();
+    Shape::new(positions, normals, indices)
+}
+
+/// A point of an ellipse.
+fn ellipse_point(radii: Vector2<f32>, angle: f32) -> Vector2<f32> {
+    Vector2::new(radii.x * angle.cos(), radii.y * angle.sin())
+}
+
+/// A rectangle.
+/// # Arguments
+/// * `size` - The width and height.
+pub fn rectangle(size: Vector2<f32>) -> Shape {
+    let (w, h) = (size.x / 2.0, size.y / 2.0);
+    let positions = vec![
+        Vector2::new(-w, -h),
+        Vector2::new( w, -h),
+        Vector2::new( w,  h),
+        Vector2::new(-w,  h),
+    ];
+
+    let normals = outline_normals(&positions);
+    Shape::new(positions, normals, vec![0, 1, 2, 0, 2, 3])
+}
+
+/// A rectangle with rounded corners.
+/// # Arguments
+/// * `size` - The width and height.
+/// * `radius` - The corner radius, clamped to half the smallest side.
+/// * `segments` - The amount of segments of each corner.
+pub fn rounded_rectangle(size: Vector2<f32>, radius: f32, segments: u32) -> Shape {
+    let (w, h) = (size.x / 2.0, size.y / 2.0);
+    let radius = radius.min(w).min(h).max(0.0);
+
+    if radius == 0.0 || segments == 0 {
+        return rectangle(size)
+    }
+
+    // The corner centers, counter clockwise from the bottom right corner.
+    let corners = [
+        Vector2::new( w - radius, -h + radius),
+        Vector2::new( w - radius,  h - radius),
+        Vector2::new(-w + radius,  h - radius),
+        Vector2::new(-w + radius, -h + radius),
+    ];
+
+    let outline = corners.iter().enumerate().flat_map(|(q, center)| {
+        let start = -PI / 2.0 + q as f32 * PI / 2.0;
+
+        (0..=segments).map(move |i| {
+            let angle = start + PI / 2.0 * i as f32 / segments as f32;
+            center + ellipse_point(Vector2::new(radius, radius), angle)
+        })
+    }).collect();
+
+    fan(outline)
+}
+
+/// An ellipse.
+/// # Arguments
+/// * `radii` - The horizontal and vertical radius.
+/// * `segments` - The amount of segments of the outline, at least 3.
+pub fn ellipse(radii: Vector2<f32>, segments: u32) -> Shape {
+    let segments = segments.max(3);
+    fan((0..segments).map(|i| ellipse_point(radii, 2.0 * PI * i as f32 / segments as f32)).collect())
+}
+
+/// A circle.
+/// # Arguments
+/// * `radius` - The radius.
+/// * `segments` - The amount of segments of the outline, at least 3.
+pub fn circle(radius: f32, segments: u32) -> Shape {
+    ellipse(Vector2::new(radius, radius), segments)
+}
+
+/// A regular polygon, with a vertex on top.
+/// # Arguments
+/// * `radius` - The distance from the center to the vertices.
+/// * `sides` - The amount of sides, at least 3.
+pub fn regular_polygon(radius: f32, sides: u32) -> Shape {
+    let sides = sides.max(3);
+    let radii = Vector2::new(radius, radius);
+
+    fan((0..sides).map(|i| ellipse_point(radii, PI / 2.0 + 2.0 * PI * i as f32 / sides as f32)).collect())
+}
+
+/// A star, with a point on top.
+/// # Arguments
+/// * `points` - The amount of points, at least 2.
+/// * `inner_radius` - The distance from the center to the hollows.
+/// * `outer_radius` - The distance from the center to the points.
+pub fn star(points: u32, inner_radius: f32, outer_radius: f32) -> Shape {
+    let count = points.max(2) * 2;
+
+    fan((0..count).map(|i| {
+        let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+        ellipse_point(Vector2::new(radius, radius), PI / 2.0 + 2.0 * PI * i as f32 / count as f32)
+    }).collect())
+}
+
+/// A thick arc of a circle.
+///
+/// The normals of the outer edge point outward, the normals of the inner edge point to the center.
+/// # Arguments
+/// * `inner_radius` - The radius of the inner edge.
+/// * `outer_radius` - The radius of the outer edge.
+/// * `start_angle` - The angle (in radians) where the arc starts.
+/// * `end_angle` - The angle (in radians) where the arc ends.
+/// * `segments` - The amount of segments, at least 1.
+pub fn arc(inner_radius: f32, outer_radius: f32, start_angle: f32, end_angle: f32, segments: u32) -> Shape {
+    let segments = segments.max(1);
+    let mut positions = Vec::with_capacity(segments as usize * 2 + 2);
+    let mut normals = Vec::with_capacity(segments as usize * 2 + 2);
+    let mut indices = Vec::with_capacity(segments as usize * 6);
+
+    for i in 0..=segments {
+        let angle = start_angle + (end_angle - start_angle) * i as f32 / segments as f32;
+        let dir = Vector2::new(angle.cos(), angle.sin());
+
+        positions.push(dir * outer_radius);
+        normals.push(dir);
+        positions.push(dir * inner_radius);
+        normals.push(-dir);
+    }
+
+    for i in 0..segments {
+        let (outer_a, inner_a, outer_b, inner_b) = (2 * i, 2 * i + 1, 2 * i + 2, 2 * i + 3);
+
+        // Keep the triangles counter clockwise when the arc goes clockwise.
+        if end_angle >= start_angle {
+            indices.extend_from_slice(&[inner_a, outer_a, outer_b, inner_a, outer_b, inner_b]);
+        } else {
+            indices.extend_from_slice(&[inner_a, outer_b, outer_a, inner_a, inner_b, outer_b]);
+        }
+    }
+
+    Shape::new(positions, normals, indices)
+}
+
+/// A ring.
+/// # Arguments
+/// * `inner_radius` - The radius of the hole.
+/// * `outer_radius` - The radius of the ring.
+/// * `segments` - The amount of segments, at least 3.
+pub fn ring(inner_radius: f32, outer_radius: f32, segments: u32) -> Shape {
+    arc(inner_radius, outer_radius, 0.0, 2.0 * PI, segments.max(3))
+}
+
+/// `true` if the segments `a1 a2` and `b1 b2` cross.
+fn segments_intersect(a1: Vector2<f32>, a2: Vector2<f32>, b1: Vector2<f32>, b2: Vector2<f32>) -> bool {
+    let d1 = cross(b1, b2, a1);
+    let d2 = cross(b1, b2, a2);
+    let d3 = cross(a1, a2, b1);
+    let d4 = cross(a1, a2, b2);
+
+    ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+}
+
+/// `true` if `p` is inside or on the edges of the counter clockwise triangle `a b c`.
+fn in_triangle(p: Vector2<f32>, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+/// Any simple polygon (without holes and self intersections), triangulated by ear clipping.
+///
+/// The points can be clockwise or counter clockwise, the shape
+/// vertices are the points in counter clockwise order. They keep
+/// their coordinates, the shape is not centered on the origin.
+/// # Arguments
+/// * `points` - The outline of the polygon.
+pub fn polygon(points: &[Vector2<f32>]) -> Result<Shape, MeshError> {
+    let mut outline = dedup(points.to_vec());
+
+    if outline.len() < 3 {
+        return Err(MeshError::Polygon(String::from("A polygon needs at least 3 distinct points")))
+    }
+
+    // Cross products and areas are compared to a tolerance scaled like them, by the size squared.
+    let epsilon = extent(&outline).powi(2) * TOLERANCE;
+    let area = double_area(&outline);
+
+    if area.abs() <= epsilon {
+        return Err(MeshError::Polygon(String::from("The polygon has no area")))
+    }
+
+    if area < 0.0 {
+        outline.reverse();
+    }
+
+    let n = outline.len();
+
+    for i in 0..n {
+        for j in i + 1..n {
+            // Adjacent edges share a point, they can't cross.
+            if j == i + 1 || (i == 0 && j == n - 1) {
+                continue
+            }
+
+            if segments_intersect(outline[i], outline[(i + 1) % n], outline[j], outline[(j + 1) % n]) {
+                return Err(MeshError::Polygon(format!("Edges {} and {} of the polygon cross", i, j)))
+            }
+        }
+    }
+
+    let mut remaining = (0..n).collect::<Vec<usize>>();
+    let mut indices = Vec::with_capacity((n - 2) * 3);
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+
+        let ear = (0..count).find_map(|i| {
+            let (prev, cur, next) = (remaining[(i + count - 1) % count], remaining[i], remaining[(i + 1) % count]);
+            let (a, b, c) = (outline[prev], outline[cur], outline[next]);
+            let turn = cross(a, b, c);
+
+            // A point on a straight edge can be removed without a triangle.
+            if turn.abs() <= epsilon {
+                return Some((i, None))
+            }
+
+            if turn < 0.0 {
+                return None
+            }
+
+            let blocked = remaining.iter()
+                .filter(|r| **r != prev && **r != cur && **r != next)
+                .any(|r| in_triangle(outline[*r], a, b, c));
+
+            if blocked { None } else { Some((i, Some([prev as u32, cur as u32, next as u32]))) }
+        });
+
+        match ear {
+            Some((i, triangle)) => {
+                if let Some(triangle) = triangle {
+                    indices.extend_from_slice(&triangle);
+                }
+
+                remaining.remove(i);
+            },
+            None => return Err(MeshError::Polygon(String::from("The polygon can't be triangulated, it may not be simple"))),
+        }
+    }
+
+    if cross(outline[remaining[0]], outline[remaining[1]], outline[remaining[2]]) > epsilon {
+        indices.extend(remaining.iter().map(|r| *r as u32));
+    }
+
+    let normals = outline_normals(&outline);
+    Ok(Shape::new(outline, normals, indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The positions of the triangles of a shape.
+    fn triangles(shape: &Shape) -> Vec<[Vector2<f32>; 3]> {
+        shape.indices.chunks(3).map(|t| {
+            [shape.vertices[t[0] as usize].pos, shape.vertices[t[1] as usize].pos, shape.vertices[t[2] as usize].pos]
+        }).collect()
+    }
+
+    /// The sum of the areas of the triangles of a shape.
+    fn area(shape: &Shape) -> f32 {
+        triangles(shape).iter().map(|[a, b, c]| cross(*a, *b, *c) / 2.0).sum()
+    }
+
+    /// Check the indices, winding and uv coordinates every shape must respect.
+    fn check(shape: &Shape) {
+        assert_eq!(shape.indices.len() % 3, 0);
+        assert!(shape.indices.iter().all(|i| (*i as usize) < shape.vertices.len()));
+
+        for [a, b, c] in triangles(shape) {
+            assert!(cross(a, b, c) > 0.0, "{:?} {:?} {:?} is not counter clockwise", a, b, c);
+        }
+
+        for v in shape.vertices.iter() {
+            assert!((0.0..=1.0).contains(&v.uv.x) && (0.0..=1.0).contains(&v.uv.y), "uv {:?} out of [0, 1]", v.uv);
+        }
+    }
+
+    /// Check that the normals of the outline (every vertex but the fan center) point outward.
+    fn check_outward(shape: &Shape, skip_center: bool) {
+        for v in shape.vertices.iter().skip(if skip_center { 1 } else { 0 }) {
+            assert!(v.norm.dot(v.pos) > 0.0, "normal {:?} at {:?} points inward", v.norm, v.pos);
+        }
+    }
+
+    fn points(coords: &[(f32, f32)]) -> Vec<Vector2<f32>> {
+        coords.iter().map(|(x, y)| Vector2::new(*x, *y)).collect()
+    }
+
+    #[test]
+    fn rectangle_counts_and_normals() {
+        let shape = rectangle(Vector2::new(4.0, 2.0));
+
+        assert_eq!(shape.vertices.len(), 4);
+        assert_eq!(shape.indices.len(), 6);
+        check(&shape);
+        check_outward(&shape, false);
+        assert!((area(&shape) - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rounded_rectangle_counts_and_normals() {
+        let shape = rounded_rectangle(Vector2::new(4.0, 2.0), 0.5, 4);
+
+        assert_eq!(shape.vertices.len(), 1 + 4 * 5);
+        assert_eq!(shape.triangle_count(), 4 * 5);
+        check(&shape);
+        check_outward(&shape, true);
+
+        // Corners as big as the shape: the straight edges vanish and their points merge.
+        let shape = rounded_rectangle(Vector2::new(2.0, 2.0), 1.0, 4);
+        assert_eq!(shape.vertices.len(), 1 + 4 * 4);
+        check(&shape);
+
+        assert_eq!(rounded_rectangle(Vector2::new(2.0, 2.0), 0.0, 4).vertices.len(), 4);
+    }
+
+    #[test]
+    fn fan_shapes_counts_and_normals() {
+        let shape = ellipse(Vector2::new(3.0, 1.0), 16);
+        assert_eq!(shape.vertices.len(), 17);
+        assert_eq!(shape.indices.len(), 3 * 16);
+        check(&shape);
+        check_outward(&shape, true);
+
+        let shape = circle(1.0, 1);
+        assert_eq!(shape.vertices.len(), 4);
+        assert_eq!(shape.triangle_count(), 3);
+        check(&shape);
+
+        let shape = regular_polygon(2.0, 6);
+        assert_eq!(shape.vertices.len(), 7);
+        assert_eq!(shape.triangle_count(), 6);
+        assert!((shape.vertices[1].pos - Vector2::new(0.0, 2.0)).magnitude() < 1e-5);
+        check(&shape);
+        check_outward(&shape, true);
+
+        let shape = star(5, 0.5, 1.0);
+        assert_eq!(shape.vertices.len(), 11);
+        assert_eq!(shape.triangle_count(), 10);
+        check(&shape);
+    }
+
+    #[test]
+    fn arc_and_ring_counts_and_normals() {
+        for (start, end) in [(0.0, PI), (PI, 0.0)] {
+            let shape = arc(1.0, 2.0, start, end, 8);
+
+            assert_eq!(shape.vertices.len(), 18);
+            assert_eq!(shape.triangle_count(), 16);
+            check(&shape);
+
+            for (i, v) in shape.vertices.iter().enumerate() {
+                let outer = i % 2 == 0;
+                assert_eq!(v.norm.dot(v.pos) > 0.0, outer, "normal {:?} at {:?}", v.norm, v.pos);
+            }
+        }
+
+        let shape = ring(1.0, 2.0, 2);
+        assert_eq!(shape.vertices.len(), 8);
+        assert_eq!(shape.triangle_count(), 6);
+        check(&shape);
+    }
+
+    #[test]
+    fn polygon_concave() {
+        // An L.
+        let l = points(&[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)]);
+        let shape = polygon(&l).unwrap();
+        assert_eq!(shape.triangle_count(), 4);
+        assert!((area(&shape) - 3.0).abs() < 1e-5);
+        check(&shape);
+
+        // A comb, with three teeth going up.
+        let comb = points(&[
+            (0.0, 0.0), (5.0, 0.0), (5.0, 3.0), (4.0, 3.0), (4.0, 1.0), (3.0, 1.0),
+            (3.0, 3.0), (2.0, 3.0), (2.0, 1.0), (1.0, 1.0), (1.0, 3.0), (0.0, 3.0),
+        ]);
+        let shape = polygon(&comb).unwrap();
+        assert_eq!(shape.triangle_count(), 10);
+        assert!((area(&shape) - 11.0).abs() < 1e-5);
+        check(&shape);
+    }
+
+    #[test]
+    fn polygon_clockwise_input() {
+        let mut l = points(&[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)]);
+        l.reverse();
+
+        let shape = polygon(&l).unwrap();
+        assert_eq!(shape.triangle_count(), 4);
+        assert!((area(&shape) - 3.0).abs() < 1e-5);
+        check(&shape);
+        assert!(double_area(&shape.vertices.iter().map(|v| v.pos).collect::<Vec<_>>()) > 0.0);
+    }
+
+    #[test]
+    fn polygon_collinear_points() {
+        // A square with a point in the middle of each side and a duplicated corner.
+        let square = points(&[
+            (0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (2.0, 2.0),
+            (2.0, 2.0), (1.0, 2.0), (0.0, 2.0), (0.0, 1.0),
+        ]);
+        let shape = polygon(&square).unwrap();
+
+        assert_eq!(shape.vertices.len(), 8);
+        assert!((area(&shape) - 4.0).abs() < 1e-5);
+        check(&shape);
+    }
+
+    #[test]
+    fn polygon_rejects_invalid_input() {
+        let bow_tie = points(&[(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]);
+        assert!(matches!(polygon(&bow_tie), Err(MeshError::Polygon(_))));
+
+        let line = points(&[(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (3.0, 3.0)]);
+        assert!(matches!(polygon(&line), Err(MeshError::Polygon(_))));
+
+        let duplicated = points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (0.0, 0.0)]);
+        assert!(matches!(polygon(&duplicated), Err(MeshError::Polygon(_))));
+
+        assert!(matches!(polygon(&[]), Err(MeshError::Polygon(_))));
+    }
+
+    #[test]
+    fn polygon_tolerance_scales_with_size() {
+        let l = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+
+        // Far below an absolute epsilon of area.
+        let tiny = l.iter().map(|(x, y)| Vector2::new(x * 1e-4, y * 1e-4)).collect::<Vec<_>>();
+        let shape = polygon(&tiny).unwrap();
+        assert_eq!(shape.triangle_count(), 4);
+        check(&shape);
+
+        // The float noise of big coordinates is far above an absolute epsilon.
+        let huge = l.iter().map(|(x, y)| Vector2::new(x * 1e5 + 0.3, y * 1e5 + 0.7)).collect::<Vec<_>>();
+        let shape = polygon(&huge).unwrap();
+        assert_eq!(shape.triangle_count(), 4);
+        check(&shape);
+    }
+}
@@ -0,0 +1,356 @@
+
+use std::{collections::HashMap, rc::Rc};
+
+use serde::Deserialize;
+
+use crate::core::{
+    rect::Rect,
+    gl::texture::Texture,
+};
+
+/// What happens when an animation reaches its last frame.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopMode {
+    /// Stop on the last frame.
+    Once,
+
+    /// Restart from the first frame.
+    Loop,
+
+    /// Play backward until the first frame, then forward again.
+    PingPong,
+}
+
+/// A named event fired when an animation enter a frame.
+#[derive(Clone, Deserialize)]
+pub struct AnimationEvent {
+    /// The index of the frame (in the clip) that fire the event.
+    pub frame: usize,
+
+    /// The event name.
+    pub name: String,
+}
+
+/// A sequence of frames of a sprite sheet.
+pub struct AnimationClip {
+    /// The sheet frame index of each clip frame.
+    pub frames: Vec<usize>,
+
+    /// The duration (in seconds) of each clip frame.
+    pub durations: Vec<f32>,
+
+    /// What happens when the clip reaches its last frame.
+    pub mode: LoopMode,
+
+    /// The events fired by the clip.
+    pub events: Vec<AnimationEvent>,
+}
+
+impl AnimationClip {
+    /// Create new `AnimationClip` where all frames have the same duration.
+    /// # Arguments
+    /// * `frames` - The sheet frame index of each clip frame.
+    /// * `duration` - The duration (in seconds) of each frame, finite and not negative.
+    /// * `mode` - What happens when the clip reaches its last frame.
+    pub fn new(frames: Vec<usize>, duration: f32, mode: LoopMode) -> Result<Self, SpriteSheetError> {
+        let durations = vec![duration; frames.len()];
+        AnimationClip::with_durations(frames, durations, mode)
+    }
+
+    /// Create new `AnimationClip` where each frame has its own duration.
+    /// # Arguments
+    /// * `frames` - The sheet frame index of each clip frame.
+    /// * `durations` - The duration (in seconds) of each frame, finite and not negative.
+    /// * `mode` - What happens when the clip reaches its last frame.
+    pub fn with_durations(frames: Vec<usize>, durations: Vec<f32>, mode: LoopMode) -> Result<Self, SpriteSheetError> {
+        if durations.len() != frames.len() {
+            let msg = format!("The clip has {} frames but {} durations", frames.len(), durations.len());
+            return Err(SpriteSheetError::new("Clip", &msg))
+        }
+
+        if let Some(duration) = durations.iter().find(|d| !d.is_finite() || **d < 0.0) {
+            let msg = format!("Invalid frame duration {}", duration);
+            return Err(SpriteSheetError::new("Clip", &msg))
+        }
+
+        Ok(Self {
+            frames,
+            durations,
+            mode,
+            events: Vec::new(),
+        })
+    }
+
+    /// Fire an event when the clip enter a frame.
+    /// # Arguments
+    /// * `frame` - The index of the frame in the clip.
+    /// * `name` - The event name.
+    pub fn with_event(mut self, frame: usize, name: &str) -> Self {
+        self.events.push(AnimationEvent { frame, name: String::from(name) });
+        self
+    }
+
+    /// The amount of frames in the clip.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// `true` if the clip has no frame.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// A sprite sheet error.
+#[derive(Debug)]
+pub struct SpriteSheetError {
+    /// Kind of error.
+    pub kind: String,
+
+    /// The error description.
+    pub message: String,
+}
+
+impl SpriteSheetError {
+    /// Create new sprite sheet error.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of error.
+    /// * `message` - The error description.
+    pub fn new(kind: &str, message: &str) -> SpriteSheetError {
+        SpriteSheetError {
+            kind: String::from(kind),
+            message: String::from(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for SpriteSheetError {
+    /// Convert an `std::io::Error` to a `SpriteSheetError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: std::io::Error) -> Self {
+        SpriteSheetError::new("io", &error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for SpriteSheetError {
+    /// Convert a `serde_json::Error` to a `SpriteSheetError`
+    ///
+    /// # Arguments
+    /// * `error` - The error to convert.
+    fn from(error: serde_json::Error) -> Self {
+        SpriteSheetError::new("json", &error.to_string())
+    }
+}
+
+/// A frame as described in a sprite sheet file,
+/// in pixels from the top left corner of the image.
+#[derive(Deserialize)]
+struct JsonFrame {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+/// A clip as described in a sprite sheet file.
+#[derive(Deserialize)]
+struct JsonClip {
+    frames: Vec<usize>,
+    #[serde(default)]
+    duration: Option<f32>,
+    #[serde(default)]
+    durations: Option<Vec<f32>>,
+    #[serde(default = "default_loop_mode")]
+    mode: LoopMode,
+    #[serde(default)]
+    events: Vec<AnimationEvent>,
+}
+
+fn default_loop_mode() -> LoopMode {
+    LoopMode::Loop
+}
+
+/// A sprite sheet file.
+#[derive(Deserialize)]
+struct JsonSpriteSheet {
+    frames: Vec<JsonFrame>,
+    #[serde(default)]
+    clips: HashMap<String, JsonClip>,
+}
+
+/// A texture cut in frames, and the animation
+/// clips made from these frames.
+pub struct SpriteSheet {
+    /// The texture the frames are cut from.
+    pub texture: Rc<Texture>,
+
+    /// The region (in uv coordinates) of each frame.
+    pub frames: Vec<Rect>,
+
+    /// The animation clips by name.
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+impl SpriteSheet {
+    /// Create a sprite sheet from frames laid out on a regular grid.
+    ///
+    /// Frames are numbered from left to right, starting from the top row.
+    ///
+    /// # Arguments
+    /// * `texture` - The texture the frames are cut from.
+    /// * `columns` - The amount of frames on each row.
+    /// * `rows` - The amount of rows.
+    pub fn from_grid(texture: Rc<Texture>, columns: u32, rows: u32) -> Self {
+        let (w, h) = (1.0 / columns as f32, 1.0 / rows as f32);
+        let mut frames = Vec::with_capacity((columns * rows) as usize);
+
+        for row in 0..rows {
+            for column in 0..columns {
+                frames.push(Rect::new(column as f32 * w, 1.0 - (row + 1) as f32 * h, w, h));
+            }
+        }
+
+        Self {
+            texture,
+            frames,
+            clips: HashMap::new(),
+        }
+    }
+
+    /// Create a sprite sheet from a json description.
+    ///
+    /// ```json
+    /// {
+    ///     "frames": [ { "x": 0, "y": 0, "w": 16, "h": 16 }, { "x": 16, "y": 0, "w": 16, "h": 16 } ],
+    ///     "clips": {
+    ///         "run": { "frames": [0, 1], "duration": 0.1, "mode": "loop", "events": [ { "frame": 1, "name": "step" } ] }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Frames are in pixels from the top left corner of the image.
+    ///
+    /// # Arguments
+    /// * `texture` - The texture the frames are cut from.
+    /// * `path` - The path of the json file.
+    pub fn from_json(texture: Rc<Texture>, path: &str) -> Result<Self, SpriteSheetError> {
+        let src = std::fs::read_to_string(path)?;
+        let sheet: JsonSpriteSheet = serde_json::from_str(&src)?;
+
+        let (width, height) = texture.get_size();
+        let (width, height) = (width as f32, height as f32);
+
+        let frames = sheet.frames.iter().map(|f| Rect::new(
+            f.x as f32 / width,
+            1.0 - (f.y + f.h) as f32 / height,
+            f.w as f32 / width,
+            f.h as f32 / height,
+        )).collect::<Vec<Rect>>();
+
+        let mut clips = HashMap::with_capacity(sheet.clips.len());
+
+        for (name, clip) in sheet.clips {
+            if let Some(frame) = clip.frames.iter().find(|f| **f >= frames.len()) {
+                let msg = format!("Clip '{}' use frame {} but the sheet only has {} frames", name, frame, frames.len());
+                return Err(SpriteSheetError::new("Clip", &msg))
+            }
+
+            let durations = match (clip.durations, clip.duration) {
+                (Some(durations), _) => durations,
+                (None, Some(duration)) => vec![duration; clip.frames.len()],
+                (None, None) => {
+                    let msg = format!("Clip '{}' has no duration", name);
+                    return Err(SpriteSheetError::new("Clip", &msg))
+                },
+            };
+
+            let animation = AnimationClip::with_durations(clip.frames, durations, clip.mode)
+                .map_err(|e| SpriteSheetError::new(&e.kind, &format!("Clip '{}': {}", name, e.message)))?;
+
+            clips.insert(name, AnimationClip { events: clip.events, ..animation });
+        }
+
+        Ok(Self { texture, frames, clips })
+    }
+
+    /// Add an animation clip to the sheet.
+    /// # Arguments
+    /// * `name` - The clip name.
+    /// * `clip` - The clip.
+    pub fn add_clip(&mut self, name: &str, clip: AnimationClip) {
+        self.clips.insert(String::from(name), clip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::temp_dir::TempDir;
+
+    /// Write a sprite sheet file and load it.
+    fn load(name: &str, content: &str) -> Result<SpriteSheet, SpriteSheetError> {
+        let dir = TempDir::new(&format!("sheet-{}", name));
+        let path = dir.write("sheet.json", content);
+
+        SpriteSheet::from_json(Rc::new(Texture::headless(64, 32)), path.to_str().unwrap())
+    }
+
+    const FRAMES: &str = r#""frames": [ { "x": 0, "y": 0, "w": 16, "h": 16 }, { "x": 16, "y": 8, "w": 32, "h": 24 } ]"#;
+
+    #[test]
+    fn loads_frames_and_clips() {
+        let sheet = load("valid", &format!(r#"{{
+            {},
+            "clips": {{
+                "run": {{ "frames": [0, 1], "duration": 0.1, "events": [ {{ "frame": 1, "name": "step" }} ] }},
+                "jump": {{ "frames": [1, 0, 1], "durations": [0.1, 0.2, 0.3], "mode": "ping_pong" }}
+            }}
+        }}"#, FRAMES)).unwrap();
+
+        // From the top left corner in pixels to the bottom left corner in uv.
+        let rect = |r: Rect| (r.x, r.y, r.w, r.h);
+        assert_eq!(rect(sheet.frames[0]), (0.0, 0.5, 0.25, 0.5));
+        assert_eq!(rect(sheet.frames[1]), (0.25, 0.0, 0.5, 0.75));
+
+        let run = &sheet.clips["run"];
+        assert_eq!(run.frames, vec![0, 1]);
+        assert_eq!(run.durations, vec![0.1, 0.1]);
+        assert!(run.mode == LoopMode::Loop);
+        assert_eq!(run.events[0].name, "step");
+
+        let jump = &sheet.clips["jump"];
+        assert_eq!(jump.durations, vec![0.1, 0.2, 0.3]);
+        assert!(jump.mode == LoopMode::PingPong);
+    }
+
+    #[test]
+    fn rejects_invalid_clips() {
+        let invalid = [
+            r#""a": { "frames": [0, 2], "duration": 0.1 }"#,
+            r#""a": { "frames": [0, 1], "durations": [0.1] }"#,
+            r#""a": { "frames": [0, 1], "durations": [0.1, 0.2, 0.3] }"#,
+            r#""a": { "frames": [0, 1] }"#,
+            r#""a": { "frames": [0, 1], "duration": -1.0 }"#,
+            r#""a": { "frames": [0], "duration": 0.1, "mode": "bounce" }"#,
+        ];
+
+        for (i, clip) in invalid.iter().enumerate() {
+            let sheet = load(&format!("invalid-{}", i), &format!(r#"{{ {}, "clips": {{ {} }} }}"#, FRAMES, clip));
+            assert!(sheet.is_err(), "{} was accepted", clip);
+        }
+
+        assert!(matches!(load("syntax", "{ \"frames\": ["), Err(e) if e.kind == "json"));
+    }
+
+    #[test]
+    fn validates_clip_durations() {
+        assert_eq!(AnimationClip::new(vec![0, 1, 2], 0.5, LoopMode::Once).unwrap().durations, vec![0.5; 3]);
+        assert!(AnimationClip::new(vec![0, 1], f32::NAN, LoopMode::Once).is_err());
+        assert!(AnimationClip::with_durations(vec![0, 1], vec![0.1], LoopMode::Loop).is_err());
+        assert!(AnimationClip::with_durations(vec![0, 1], vec![0.1, -0.1], LoopMode::Loop).is_err());
+    }
+}
@@ -1,4 +1,3 @@
-
 use super::world::{EntityComponentManager};
 
 pub trait Sys {
@@ -7,4 +6,8 @@ pub trait Sys {
 
     /// Called once per frame.
     fn on_update(&self, _: &mut EntityComponentManager) {}
-}
\ No newline at end of file
+
+    /// Called once when the world shut down, systems
+    /// are shut down in the reverse order of registration.
+    fn on_shutdown(&self, _: &mut EntityComponentManager) {}
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+/// A directory of test files, removed with its content when dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Create new empty `TempDir` in the system temporary directory.
+    /// # Arguments
+    /// * `name` - The directory name, unique among the tests.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cacti-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        TempDir(dir)
+    }
+
+    /// The path of a file in the directory.
+    /// # Arguments
+    /// * `name` - The file path, relative to the directory.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+
+    /// Write a file (and its parent directories) in the directory.
+    ///
+    /// Returns the path of the file.
+    /// # Arguments
+    /// * `name` - The file path, relative to the directory.
+    /// * `content` - The file content.
+    pub fn write(&self, name: &str, content: &str) -> PathBuf {
+        let path = self.path(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,6 +1,12 @@
 use std::any::{Any, TypeId};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
 
-use super::{entity::Entity, managers::{
+use crate::components::smc::AppExit;
+
+use super::{entity::Entity, events::Events, managers::{
         component_manager::ComponentsManager, 
         entity_manager::EntityManager, 
         system_manager::SystemManager
@@ -62,7 +68,7 @@ impl EntityComponentManager {
         self.component_manager.get_component_mut::<T>(entity)
     }
 
-    pub fn get_entities_with_type(&self, type_id: &TypeId) -> Option<&Vec<Entity>>{
+    pub fn get_entities_with_type(&self, type_id: &TypeId) -> Option<&[Entity]>{
         self.component_manager.get_entities_with_component(&type_id)
     }
 
@@ -80,18 +86,69 @@ impl EntityComponentManager {
         
         for type_id in type_ids {
             match self.get_entities_with_type(&type_id) {
-                Some(e) => entities.extend(e),
+                Some(e) => entities.extend_from_slice(e),
                 _ => {}
             }
         }
 
         entities
     }
+
+    /// Send an event to all systems.
+    ///
+    /// The event stay readable until the events of
+    /// the same type are cleared with `clear_events`, usually by the
+    /// sender at the start of its next update: the systems registered
+    /// before the sender read it one frame late (see `Events`).
+    /// # Arguments
+    /// * `event` - The event to send.
+    pub fn send_event<T: Any>(&mut self, event: T) {
+        let entity = match <(Events<T>,)>::query_first_from(self) {
+            Some(entity) => entity,
+            None => {
+                let entity = self.create_entity().unwrap();
+                self.add_component(&entity, Events::<T>::new());
+                entity
+            },
+        };
+
+        self.get_component_mut::<Events<T>>(&entity).unwrap().send(event);
+    }
+
+    /// Get all the events of a certain type, oldest first.
+    pub fn read_events<T: Any>(&self) -> &[T] {
+        match <(Events<T>,)>::query_first_from(self) {
+            Some(entity) => self.get_component::<Events<T>>(&entity).unwrap().as_slice(),
+            None => &[],
+        }
+    }
+
+    /// Shut down the world at the end of the frame.
+    ///
+    /// Same as sending an `AppExit` event.
+    /// # Arguments
+    /// * `code` - The exit code returned by `World::start_loop`.
+    pub fn request_exit(&mut self, code: i32) {
+        self.send_event(AppExit(code));
+    }
+
+    /// Remove all the events of a certain type.
+    pub fn clear_events<T: Any>(&mut self) {
+        if let Some(entity) = <(Events<T>,)>::query_first_from(self) {
+            self.get_component_mut::<Events<T>>(&entity).unwrap().clear();
+        }
+    }
 }
 
+/// The exit code used when the world is shut down by Ctrl-C (128 + SIGINT).
+pub const INTERRUPT_EXIT_CODE: i32 = 130;
+
 pub struct World {
     pub entity_component_manager: EntityComponentManager,
     pub system_manager: SystemManager,
+
+    /// Set by the Ctrl-C handler, see `exit_on_interrupt`.
+    interrupted: Option<Arc<AtomicBool>>,
 }
 
 impl World {
@@ -105,7 +162,8 @@ impl World {
     pub fn new(max_entities: usize, max_components_type: usize, max_components_per_types: usize, max_systems: usize) -> Self {
         Self {
             entity_component_manager: EntityComponentManager::new(max_entities, max_components_type, max_components_per_types),
-            system_manager: SystemManager::new(max_systems)
+            system_manager: SystemManager::new(max_systems),
+            interrupted: None,
         }
     }
 
@@ -113,15 +171,69 @@ impl World {
         self.system_manager.register(system)
     }
 
-    pub fn start_loop(&mut self) {
-    
+    /// Shut down the world gracefully on Ctrl-C (SIGINT) with `INTERRUPT_EXIT_CODE`.
+    ///
+    /// Mostly useful for headless worlds (without window) where
+    /// nothing else can stop the loop. Only one handler can be
+    /// installed per process, fails if one is already installed.
+    pub fn exit_on_interrupt(&mut self) -> Result<(), ctrlc::Error> {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let flag = interrupted.clone();
+
+        ctrlc::set_handler(move || flag.store(true, Ordering::SeqCst))?;
+        self.interrupted = Some(interrupted);
+        Ok(())
+    }
+
+    /// Shut down the world at the end of the frame.
+    /// # Arguments
+    /// * `code` - The exit code returned by `start_loop`.
+    pub fn request_exit(&mut self, code: i32) {
+        self.entity_component_manager.request_exit(code);
+    }
+
+    /// Start all systems.
+    ///
+    /// Only needed when the world is driven with `step`, `start_loop` already call it.
+    pub fn init(&mut self) {
         self.system_manager.init(&mut self.entity_component_manager);
+    }
+
+    /// Run all systems once (a single frame).
+    ///
+    /// Returns `false` once the world was shut down.
+    pub fn step(&mut self) -> bool {
+        if let Some(interrupted) = &self.interrupted {
+            if interrupted.swap(false, Ordering::SeqCst) {
+                self.entity_component_manager.request_exit(INTERRUPT_EXIT_CODE);
+            }
+        }
+
+        self.system_manager.update(&mut self.entity_component_manager)
+    }
+
+    /// Run the shutdown hook of all systems, in the reverse order of registration.
+    ///
+    /// Only needed when the world is driven with `step`, `start_loop` already call it.
+    /// Returns the exit code.
+    pub fn shutdown(&mut self) -> i32 {
+        self.system_manager.shutdown(&mut self.entity_component_manager)
+    }
+
+    /// Run the world until it is shut down.
+    ///
+    /// Returns the exit code.
+    pub fn start_loop(&mut self) -> i32 {
+    
+        self.init();
         
         loop {
-            if !self.system_manager.update(&mut self.entity_component_manager) {
+            if !self.step() {
                 break;
             }
         }
+
+        self.shutdown()
     }
 
 }
@@ -14,19 +14,23 @@ pub struct BeforeRenderSys;
 impl Sys for BeforeRenderSys {
 
     fn on_update(&self, world: &mut EntityComponentManager) {
-        match <(Window,)>::query_first_from(&world) {
-            Some(entity) => {
-                let window_component = world.get_component::<Window>(&entity).unwrap();
-
-                if !window_component.raw.should_close() {
-                    unsafe {
-                        gl::FrontFace(gl::CW);
-                        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
-                        gl::ClearColor(0.15, 0.15, 0.15, 1.0);
-                    }
+        for entity in <(Window,)>::query_from(&world) {
+            let window_component = world.get_component_mut::<Window>(&entity).unwrap();
+
+            if !window_component.raw.should_close() {
+                window_component.make_current();
+
+                unsafe {
+                    gl::FrontFace(gl::CCW);
+                    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                    gl::ClearColor(0.15, 0.15, 0.15, 1.0);
                 }
-            },
-            None => {},
+            }
+        }
+
+        // Give the context back to the primary window for the other systems.
+        if let Some(entity) = Window::find_primary(world) {
+            world.get_component_mut::<Window>(&entity).unwrap().make_current();
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,168 @@
+use std::rc::Rc;
+
+use cgmath::{
+    Matrix4,
+    prelude::*,
+};
+
+use crate::core::{
+    entity::Entity,
+    gl::{
+        builtin::{BuiltinShader, LineVertex},
+        material::{BlendMode, Material},
+    },
+    rect::Rect,
+    sys::Sys,
+    world::{EntityComponentManager, EntitySelector},
+};
+
+use crate::components::{
+    camera2d::Camera2D,
+    debug_draw::{self, DebugDraw, DebugDrawMesh},
+    mesh_2d::{Mesh2D, Topology},
+    render_errors::RenderErrors,
+    window::Window,
+};
+
+/// Draw the `DebugDraw` shapes on top of the primary window.
+///
+/// Must be registered after `RenderSys` and before `AfterRenderSys`.
+/// The shapes are drawn with every camera of the primary window, or
+/// in clip space without camera. Does nothing in release builds.
+pub struct DebugDrawSys;
+
+impl DebugDrawSys {
+
+    /// The view projection and viewport of the cameras of the primary window, sorted by draw order.
+    /// # Arguments
+    /// * `target_size` - The size of the primary window framebuffer in pixels.
+    fn cameras(&self, world: &EntityComponentManager, target_size: (u32, u32)) -> Vec<(Matrix4<f32>, Rect)> {
+        let mut cameras = <(Camera2D,)>::query_from(world).iter()
+            .map(|e| world.get_component::<Camera2D>(e).unwrap())
+            .filter(|camera| match camera.target {
+                Some(target) => world.get_component::<Window>(&target).is_some_and(|w| w.primary),
+                None => true,
+            })
+            .collect::<Vec<&Camera2D>>();
+
+        cameras.sort_by_key(|c| c.order);
+
+        if cameras.is_empty() {
+            let (w, h) = target_size;
+            return vec![(Matrix4::identity(), Rect::new(0.0, 0.0, w as f32, h as f32))]
+        }
+
+        cameras.iter()
+            .map(|camera| (camera.view_projection(target_size), camera.viewport_pixels(target_size)))
+            .collect()
+    }
+
+    /// Draw the lines with the debug mesh, if it was created.
+    /// # Arguments
+    /// * `entity` - The entity of the `DebugDraw`.
+    /// * `vertices` - The vertices of the lines.
+    /// * `target_size` - The size of the primary window framebuffer in pixels.
+    fn draw(&self, world: &mut EntityComponentManager, entity: Entity, vertices: Vec<LineVertex>, target_size: (u32, u32)) {
+        let cameras = self.cameras(world, target_size);
+        let mut errors = Vec::new();
+
+        if let Some(DebugDrawMesh(mesh)) = world.get_component_mut::<DebugDrawMesh>(&entity) {
+            errors.extend(mesh.set_vertices(vertices).err().map(|e| e.to_string()));
+            mesh.upload();
+            mesh.prepare_context();
+
+            let shader = mesh.material.get_shader().clone();
+            errors.extend(mesh.material.apply().err().map(|e| e.to_string()));
+
+            for (view_projection, viewport) in cameras {
+                let Rect { x, y, w, h } = viewport;
+
+                unsafe { gl::Viewport(x as i32, y as i32, w as i32, h as i32) };
+                errors.extend(shader.set_uniform("view_projection", view_projection).err().map(|e| e.to_string()));
+                mesh.draw();
+            }
+
+            unsafe { gl::Viewport(0, 0, target_size.0 as i32, target_size.1 as i32) };
+        }
+
+        for error in errors {
+            RenderErrors::report_to(world, error);
+        }
+    }
+}
+
+impl Sys for DebugDrawSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, DebugDraw::new());
+
+        if !debug_draw::ENABLED {
+            return
+        }
+
+        let mesh = BuiltinShader::Line.get()
+            .map_err(|e| e.to_string())
+            .and_then(|shader| {
+                let material = Material::new(shader).with_blend(BlendMode::Alpha);
+                Mesh2D::empty::<LineVertex>(Topology::Lines, Rc::new(material), true).map_err(|e| e.to_string())
+            });
+
+        match mesh {
+            Ok(mesh) => world.add_component(&e, DebugDrawMesh(mesh)),
+            Err(error) => RenderErrors::report_to(world, format!("Failed to create the debug draw mesh: {}", error)),
+        }
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        if !debug_draw::ENABLED {
+            return
+        }
+
+        let entity = match <(DebugDraw,)>::query_first_from(world) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        let (enabled, vertices) = {
+            let debug = world.get_component::<DebugDraw>(&entity).unwrap();
+            (debug.enabled, debug.vertices())
+        };
+
+        let target_size = Window::find_primary(world).map(|w| world.get_component::<Window>(&w).unwrap().framebuffer_size);
+
+        // Without mesh or primary window nothing is drawn, the shapes still expire.
+        if let Some(target_size) = target_size {
+            if enabled && !vertices.is_empty() {
+                self.draw(world, entity, vertices, target_size);
+            }
+        }
+
+        world.get_component_mut::<DebugDraw>(&entity).unwrap().expire();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::color::Color;
+    use cgmath::Vector2;
+
+    #[test]
+    fn shapes_expire_without_mesh_nor_window() {
+        let mut world = EntityComponentManager::new(8, 8, 8);
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, DebugDraw::new());
+
+        {
+            let debug = world.get_component_mut::<DebugDraw>(&e).unwrap();
+            debug.line(Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Color(1.0, 1.0, 1.0, 1.0), None);
+            debug.line(Vector2::new(0.0, 0.0), Vector2::new(0.0, 1.0), Color(1.0, 1.0, 1.0, 1.0), Some(60.0));
+        }
+
+        DebugDrawSys.on_update(&mut world);
+
+        let expected = if debug_draw::ENABLED { 1 } else { 0 };
+        assert_eq!(world.get_component::<DebugDraw>(&e).unwrap().line_count(), expected);
+    }
+}
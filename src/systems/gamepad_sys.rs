@@ -0,0 +1,111 @@
+
+use glfw::{GamepadAxis, GamepadButton, JoystickId};
+
+use crate::core::{sys::Sys, world::{EntityComponentManager, EntitySelector}};
+
+use crate::components::{
+    gamepads::{Gamepads, GamepadEvent, AXIS_COUNT, BUTTON_COUNT},
+    window::Window,
+};
+
+/// The state of a joystick slot read from GLFW.
+struct JoystickState {
+    id: JoystickId,
+    name: String,
+    buttons: [bool; BUTTON_COUNT],
+    axes: [f32; AXIS_COUNT],
+}
+
+/// Poll the gamepads through GLFW once per frame and
+/// update the `Gamepads` component.
+///
+/// Connections and disconnections are sent as `GamepadEvent`.
+pub struct GamepadSys {
+    deadzone: f32,
+}
+
+impl GamepadSys {
+
+    /// Create new `GamepadSys`
+    /// # Arguments
+    /// * `deadzone` - Stick values under this threshold are read as `0.0`.
+    pub fn new(deadzone: f32) -> Self {
+        Self {
+            deadzone,
+        }
+    }
+
+    /// Read the state of every joystick slot that contains a gamepad.
+    fn poll(&self, window: &Window) -> Vec<JoystickState> {
+        (0..16).filter_map(JoystickId::from_i32).filter_map(|id| {
+            let joystick = window.glfw.get_joystick(id);
+
+            if !joystick.is_present() || !joystick.is_gamepad() {
+                return None
+            }
+
+            let state = joystick.get_gamepad_state()?;
+            let mut buttons = [false; BUTTON_COUNT];
+            let mut axes = [0.0; AXIS_COUNT];
+
+            for (i, pressed) in buttons.iter_mut().enumerate() {
+                let button = GamepadButton::from_i32(i as i32)?;
+                *pressed = state.get_button_state(button) != glfw::Action::Release;
+            }
+
+            for (i, value) in axes.iter_mut().enumerate() {
+                *value = state.get_axis(GamepadAxis::from_i32(i as i32)?);
+            }
+
+            Some(JoystickState {
+                id,
+                name: joystick.get_gamepad_name().unwrap_or_default(),
+                buttons,
+                axes,
+            })
+        }).collect()
+    }
+}
+
+impl Sys for GamepadSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, Gamepads::new(self.deadzone));
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        world.clear_events::<GamepadEvent>();
+
+        let states = match <(Window,)>::query_first_from(world) {
+            Some(entity) => self.poll(world.get_component::<Window>(&entity).unwrap()),
+            None => return,
+        };
+
+        let gamepads_entity = match <(Gamepads,)>::query_first_from(world) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        let gamepads = world.get_component_mut::<Gamepads>(&gamepads_entity).unwrap();
+        let mut events = Vec::new();
+
+        let disconnected = gamepads.iter()
+            .map(|p| p.id)
+            .filter(|id| !states.iter().any(|s| s.id == *id))
+            .collect::<Vec<JoystickId>>();
+
+        for id in disconnected {
+            events.extend(gamepads.disconnect(id));
+        }
+
+        for state in states {
+            events.extend(gamepads.connect(state.id, &state.name));
+            gamepads.set_state(state.id, state.buttons, state.axes);
+        }
+
+        for event in events {
+            world.send_event(event);
+        }
+    }
+}
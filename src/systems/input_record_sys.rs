@@ -0,0 +1,240 @@
+
+use std::{io::Write, path::PathBuf};
+
+use crate::core::{
+    sys::Sys,
+    platform_event::PlatformEvent,
+    input_record::{InputRecording, RecordedFrame, RecordingError, write_frame},
+    world::{EntityComponentManager, EntitySelector},
+};
+
+use crate::components::{
+    input_replay::{InputRecorder, InputReplay},
+    time::Time,
+    window::{Window, WindowResized},
+};
+
+/// Record the delta time and the platform events of every frame to a file.
+///
+/// Must be registered after `WindowSys` and `TimeSys`. Each frame
+/// is flushed to the file so the recording survive a crash.
+///
+/// When the file can't be created or written the recording stops
+/// and the error is sent as a `RecordingError` event, the world
+/// keeps running. The errors are cleared at the start of the next update.
+pub struct InputRecordSys {
+    path: PathBuf,
+}
+
+impl InputRecordSys {
+
+    /// Create new `InputRecordSys`
+    /// # Arguments
+    /// * `path` - The path of the recording file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+        }
+    }
+}
+
+impl Sys for InputRecordSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let file = match std::fs::File::create(&self.path) {
+            Ok(file) => file,
+            Err(e) => {
+                let message = format!("Failed to create the input recording '{}': {}", self.path.display(), e);
+                return world.send_event(RecordingError::new("io", &message))
+            },
+        };
+
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, InputRecorder { file: std::io::BufWriter::new(file) });
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        world.clear_events::<RecordingError>();
+
+        let delta = match <(Time,)>::query_first_from(world) {
+            Some(entity) => world.get_component::<Time>(&entity).unwrap().delta,
+            None => 0.0,
+        };
+
+        let frame = RecordedFrame {
+            delta,
+            events: world.read_events::<PlatformEvent>().to_vec(),
+        };
+
+        if let Some(entity) = <(InputRecorder,)>::query_first_from(world) {
+            let recorder = world.get_component_mut::<InputRecorder>(&entity).unwrap();
+
+            if let Err(e) = write_frame(&mut recorder.file, &frame).and_then(|_| recorder.file.flush()) {
+                let message = format!("Failed to record input to '{}': {}", self.path.display(), e);
+
+                world.remove_component::<InputRecorder>(&entity);
+                world.send_event(RecordingError::new("io", &message));
+            }
+        }
+    }
+}
+
+/// The exit code used when the recording played by `InputReplaySys` can't be loaded.
+pub const REPLAY_ERROR_EXIT_CODE: i32 = 1;
+
+/// Play a recording in place of the real input.
+///
+/// Each frame the platform events are replaced by the recorded
+/// ones and the `Time` advance by the recorded delta time, so
+/// `TimeSys` must not be registered. Register it after a `WindowSys`
+/// in replay mode (see `WindowSys::with_replay`), or without any
+/// window in a headless world driven by `World::step`.
+///
+/// When the recording can't be loaded the error is sent as a
+/// `RecordingError` event and the world is shut down with
+/// `REPLAY_ERROR_EXIT_CODE`. The event is cleared at the start of the next update.
+pub struct InputReplaySys {
+    path: PathBuf,
+}
+
+impl InputReplaySys {
+
+    /// Create new `InputReplaySys`
+    /// # Arguments
+    /// * `path` - The path of the recording file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+        }
+    }
+}
+
+impl Sys for InputReplaySys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let recording = match InputRecording::load(&self.path) {
+            Ok(recording) => recording,
+            Err(e) => {
+                let message = format!("Failed to load the input recording '{}': {}", self.path.display(), e.message);
+
+                world.send_event(RecordingError::new(&e.kind, &message));
+                return world.request_exit(REPLAY_ERROR_EXIT_CODE)
+            },
+        };
+
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, InputReplay { frames: recording.frames, next: 0 });
+
+        if <(Time,)>::query_first_from(world).is_none() {
+            let e = world.create_entity().unwrap();
+            world.add_component(&e, Time::new());
+        }
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        world.clear_events::<RecordingError>();
+        world.clear_events::<PlatformEvent>();
+        world.clear_events::<WindowResized>();
+
+        let frame = match <(InputReplay,)>::query_first_from(world) {
+            Some(entity) => {
+                let replay = world.get_component_mut::<InputReplay>(&entity).unwrap();
+
+                if replay.is_finished() {
+                    return
+                }
+
+                replay.next += 1;
+                replay.frames[replay.next - 1].clone()
+            },
+            None => return,
+        };
+
+        if let Some(entity) = <(Time,)>::query_first_from(world) {
+            world.get_component_mut::<Time>(&entity).unwrap().advance(frame.delta);
+        }
+
+        let mut resized = Vec::new();
+
+        // Recordings don't know about windows, the events belong to the primary window.
+        if let Some(entity) = Window::find_primary(world) {
+            let window = world.get_component_mut::<Window>(&entity).unwrap();
+            resized.extend(frame.events.iter().filter_map(|e| window.apply_resize_event(entity, e)));
+        }
+
+        for e in resized {
+            world.send_event::<WindowResized>(e);
+        }
+
+        for e in frame.events {
+            world.send_event(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{temp_dir::TempDir, world::World};
+
+    #[test]
+    fn replays_a_recording_headless() {
+        let dir = TempDir::new("replay");
+        let path = dir.write("input.rec", "frame 0.25\ncursor 1 2\nframe 0.5\nframe 0.25\nscroll 0 -1\nclose\n");
+
+        let mut world = World::new(16, 16, 16, 1);
+        world.add_system(InputReplaySys::new(path));
+        world.init();
+
+        let mut frames = Vec::new();
+
+        while world.step() {
+            let ecm = &world.entity_component_manager;
+            let time = ecm.get_component::<Time>(&<(Time,)>::query_first_from(ecm).unwrap()).unwrap();
+            frames.push((time.elapsed, ecm.read_events::<PlatformEvent>().to_vec()));
+
+            // An exit requested between two frames shuts the world down after the next frame.
+            if frames.len() == 3 {
+                world.request_exit(0);
+            }
+        }
+
+        assert_eq!(world.shutdown(), 0);
+        assert_eq!(frames, vec![
+            (0.25, vec![PlatformEvent::CursorMoved { x: 1.0, y: 2.0 }]),
+            (0.75, vec![]),
+            (1.0, vec![PlatformEvent::Scroll { x: 0.0, y: -1.0 }, PlatformEvent::CloseRequested]),
+            (1.0, vec![]),
+        ]);
+    }
+
+    #[test]
+    fn invalid_recording_shuts_down() {
+        let dir = TempDir::new("replay-invalid");
+        let path = dir.write("input.rec", "cursor 1 2\n");
+
+        let mut world = World::new(16, 16, 16, 1);
+        world.add_system(InputReplaySys::new(path));
+        world.init();
+
+        assert_eq!(world.entity_component_manager.read_events::<RecordingError>().len(), 1);
+
+        while world.step() {}
+
+        assert_eq!(world.shutdown(), REPLAY_ERROR_EXIT_CODE);
+        assert!(world.entity_component_manager.read_events::<RecordingError>().is_empty());
+    }
+
+    #[test]
+    fn record_error_is_sent() {
+        let mut world = World::new(16, 16, 16, 1);
+        world.add_system(InputRecordSys::new("/nonexistent-cacti-dir/input.rec"));
+        world.init();
+
+        assert_eq!(world.entity_component_manager.read_events::<RecordingError>().len(), 1);
+        assert!(<(InputRecorder,)>::query_first_from(&world.entity_component_manager).is_none());
+
+        assert!(world.step());
+        assert!(world.entity_component_manager.read_events::<RecordingError>().is_empty());
+    }
+}
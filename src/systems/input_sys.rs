@@ -0,0 +1,68 @@
+
+use crate::core::{
+    sys::Sys,
+    platform_event::PlatformEvent,
+    world::{EntityComponentManager, EntitySelector},
+};
+
+use crate::components::{
+    camera2d::Camera2D,
+    input::Input,
+    window::{Window, WindowEvent},
+};
+
+/// Update the `Input` component from the platform events of the frame.
+///
+/// Must be registered after `WindowSys`.
+pub struct InputSys;
+
+impl Sys for InputSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, Input::new());
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        let events = world.read_events::<PlatformEvent>().to_vec();
+
+        let input_entity = match <(Input,)>::query_first_from(&world) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        let input = world.get_component_mut::<Input>(&input_entity).unwrap();
+        input.begin_frame();
+
+        for event in events.iter() {
+            input.apply(event);
+        }
+
+        let cursor_window = world.read_events::<WindowEvent>().iter().rev()
+            .find(|e| matches!(e.event, PlatformEvent::CursorMoved { .. } | PlatformEvent::CursorEntered(true)))
+            .map(|e| e.window);
+
+        let input = world.get_component_mut::<Input>(&input_entity).unwrap();
+
+        if cursor_window.is_some() {
+            input.cursor_window = cursor_window;
+        }
+
+        let cursor = input.cursor;
+        let window_entity = input.cursor_window.or_else(|| Window::find_primary(&world));
+
+        // The cursor is in screen coordinates but cameras work with framebuffer pixels.
+        let cursor_world = window_entity.and_then(|window_entity| {
+            let window = world.get_component::<Window>(&window_entity)?;
+
+            let camera = <(Camera2D,)>::query_from(&world).into_iter()
+                .map(|e| world.get_component::<Camera2D>(&e).unwrap())
+                .filter(|c| c.target == Some(window_entity) || (c.target.is_none() && window.primary))
+                .min_by_key(|c| c.order)?;
+
+            Some(camera.screen_to_world(window.to_pixels(cursor), window.framebuffer_size))
+        });
+
+        world.get_component_mut::<Input>(&input_entity).unwrap().cursor_world = cursor_world;
+    }
+}
@@ -4,4 +4,11 @@ pub mod window_sys;
 pub mod before_render_sys;
 pub mod after_render_sys;
 pub mod procedural_square_sys;
-pub mod render_sys;
\ No newline at end of file
+pub mod render_sys;
+pub mod time_sys;
+pub mod sprite_animation_sys;
+pub mod input_sys;
+pub mod gamepad_sys;
+pub mod input_record_sys;
+pub mod shader_reload_sys;
+pub mod debug_draw_sys;
\ No newline at end of file
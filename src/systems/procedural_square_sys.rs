@@ -1,9 +1,8 @@
-use crate::core::{gl::shader::Shader, world::EntityComponentManager};
+use std::rc::Rc;
 
-use cgmath::{
-    Vector2,
-    Vector3,
-};
+use crate::core::{gl::{material::Material, shader::Shader}, shapes, world::EntityComponentManager};
+
+use cgmath::Vector2;
 
 use crate::core::{
     sys::Sys, 
@@ -13,32 +12,69 @@ use crate::core::{
 };
 
 use crate::components::{
-    mesh_2d::Vertex2D,
     mesh_2d::Mesh2D,
+    render_errors::RenderErrors,
     window::Window,
 };
 
-pub struct ProceduralSquareSys;
+const VERTEX_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/colorfull/vert.glsl");
+const FRAGMENT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/colorfull/frag.glsl");
+const VERTEX_SRC: &str = include_str!("../../shaders/colorfull/vert.glsl");
+const FRAGMENT_SRC: &str = include_str!("../../shaders/colorfull/frag.glsl");
+
+/// Draw a square with an animated color gradient.
+pub struct ProceduralSquareSys {
+    /// Build the shader from the files of the repository, so it can be hot-reloaded.
+    hot_reload: bool,
+}
+
+impl Default for ProceduralSquareSys {
+    fn default() -> Self {
+        ProceduralSquareSys::new()
+    }
+}
+
+impl ProceduralSquareSys {
+
+    /// Create new `ProceduralSquareSys` with the embedded shader.
+    pub fn new() -> Self {
+        Self {
+            hot_reload: false,
+        }
+    }
+
+    /// Build the shader from the source files of the repository instead of the
+    /// embedded copies, so `ShaderReloadSys` reloads it when they change.
+    /// # Arguments
+    /// * `hot_reload` - `true` to use the source files.
+    pub fn with_hot_reload(mut self, hot_reload: bool) -> Self {
+        self.hot_reload = hot_reload;
+        self
+    }
+}
+
 impl Sys for ProceduralSquareSys {
     
     fn on_start(&self, world: &mut EntityComponentManager) {
         let e = world.create_entity().unwrap();
 
-        let shader = Shader::new("./shaders/colorfull/vert.glsl", "./shaders/colorfull/frag.glsl").ok().unwrap();
+        let shader = if self.hot_reload {
+            Shader::new(VERTEX_PATH, FRAGMENT_PATH)
+        } else {
+            Shader::from_source(VERTEX_SRC, FRAGMENT_SRC)
+        };
 
-        let vertices = vec![
-            Vertex2D::new(Vector2::new(-1.0, -1.0), Vector2::new(-0.5, -0.5), Vector2::new(0.0, 0.0)),
-            Vertex2D::new(Vector2::new(-1.0,  1.0), Vector2::new(-0.5,  0.5), Vector2::new(0.0, 1.0)),
-            Vertex2D::new(Vector2::new( 1.0,  1.0), Vector2::new( 0.5,  0.5), Vector2::new(1.0, 1.0)),
-            Vertex2D::new(Vector2::new( 1.0, -1.0), Vector2::new( 0.5, -0.5), Vector2::new(1.0, 0.0)),
-        ];
+        let shader = match shader {
+            Ok(shader) => shader,
+            Err(e) => panic!("Failed to compile the procedural square shader: {}", e),
+        };
 
-        let indices: Vec<Vector3<u16>> = vec![
-            Vector3::new(0, 1, 2),
-            Vector3::new(0, 2, 3),
-        ];
+        let material = Rc::new(Material::new(Rc::new(shader)));
 
-        let mesh2d = Mesh2D::new(vertices, indices, shader, false);
+        let mesh2d = match shapes::rectangle(Vector2::new(2.0, 2.0)).into_mesh(material, false) {
+            Ok(mesh2d) => mesh2d,
+            Err(e) => panic!("Failed to create the procedural square mesh: {}", e),
+        };
 
         world.add_component(&e, mesh2d);
     }
@@ -52,13 +88,19 @@ impl Sys for ProceduralSquareSys {
         let time = window.glfw.get_time() as f32;
         let res  = window.raw.get_framebuffer_size();
 
-        // set the time and resolution value to the shader of all entities
-        // that have the Mesh2D component !
+        // set the time and resolution value to the material of all entities
+        // that have the Mesh2D component, RenderSys upload them when drawing !
+        let mut errors = Vec::new();
+
         for e in <(Mesh2D,)>::query_from(world) {
             let mesh = world.get_component::<Mesh2D>(&e).unwrap();
 
-            mesh.shader.set_float("time", time);
-            mesh.shader.set_vec2("screen_resolution", &[Vector2::new(res.0 as f32, res.1 as f32)]);
+            errors.extend(mesh.material.set_uniform("time", time).err());
+            errors.extend(mesh.material.set_uniform("screen_resolution", Vector2::new(res.0 as f32, res.1 as f32)).err());
+        }
+
+        for error in errors {
+            RenderErrors::report_to(world, error);
         }
     }
 }
\ No newline at end of file
@@ -1,22 +1,282 @@
 
+use std::rc::Rc;
+
+use cgmath::{
+    Matrix4,
+    Vector2,
+    prelude::*,
+};
+
 use crate::{components::{
+    camera2d::Camera2D,
+    color::Color,
     mesh_2d::Mesh2D,
+    render_errors::RenderErrors,
+    sprite::Sprite,
+    transform2d::Transform2D,
+    window::Window,
 }, core::world::EntityComponentManager};
 
 use crate::core::{
-    sys::Sys, 
+    entity::Entity,
+    rect::Rect,
+    sys::Sys,
+    gl::{
+        shader::{Shader, ShaderError},
+        object,
+        texture::Texture,
+        sprite_batch::{SpriteBatch, SpriteVertex},
+    },
     world::{
         EntitySelector,
     }
 };
 
+/// Sprite quads that share the same shader and texture.
+struct SpriteGroup {
+    shader: Rc<Shader>,
+    texture: Rc<Texture>,
+    quads: Vec<[SpriteVertex; 4]>,
+}
+
+/// What a camera needs to draw the world.
+struct CameraView {
+    view_projection: Matrix4<f32>,
+    viewport: Rect,
+    clear_color: Color,
+    order: i32,
+}
+
+/// Draw the meshes and sprites into every window.
+///
+/// Each window is drawn by the cameras that target it. Buffers, textures
+/// and shaders are shared between windows but vertex arrays are not, so
+/// meshes create a vertex array for each window they are drawn into and
+/// each window gets its own `SpriteBatch`.
+///
+/// The `view_projection` and `model` uniforms (`mat4`) are set to the shaders
+/// that use them. The uniform errors (of these uniforms and of the materials)
+/// are reported to `RenderErrors`.
 pub struct RenderSys;
+
+impl RenderSys {
+
+    /// Gather all sprites, grouped by shader & texture pair.
+    fn collect_sprites(&self, world_state: &EntityComponentManager) -> Vec<SpriteGroup> {
+        let mut groups = Vec::<SpriteGroup>::new();
+
+        for entity in <(Sprite,)>::query_from(&world_state) {
+            let sprite = world_state.get_component::<Sprite>(&entity).unwrap();
+            let pos = match world_state.get_component::<Transform2D>(&entity) {
+                Some(transform) => transform.pos,
+                None => Vector2::new(0.0, 0.0),
+            };
+
+            let quad = sprite.vertices(pos);
+            let group = groups.iter_mut().find(|g| {
+                g.shader.get_program() == sprite.shader.get_program() && g.texture.get_id() == sprite.texture.get_id()
+            });
+
+            match group {
+                Some(group) => group.quads.push(quad),
+                None => groups.push(SpriteGroup {
+                    shader: sprite.shader.clone(),
+                    texture: sprite.texture.clone(),
+                    quads: vec![quad],
+                }),
+            }
+        }
+
+        groups
+    }
+
+    /// Gather the cameras that draw into a window, sorted by draw order.
+    /// # Arguments
+    /// * `window` - The window entity.
+    /// * `primary` - `true` if the window is the primary window.
+    /// * `target_size` - The size of the render target in pixels.
+    fn collect_cameras(&self, world_state: &EntityComponentManager, window: Entity, primary: bool, target_size: (u32, u32)) -> Vec<CameraView> {
+        let mut cameras = <(Camera2D,)>::query_from(&world_state).iter().filter_map(|entity| {
+            let camera = world_state.get_component::<Camera2D>(&entity).unwrap();
+
+            match camera.target {
+                Some(target) if target != window => return None,
+                None if !primary => return None,
+                _ => {},
+            }
+
+            Some(CameraView {
+                view_projection: camera.view_projection(target_size),
+                viewport: camera.viewport_pixels(target_size),
+                clear_color: camera.clear_color,
+                order: camera.order,
+            })
+        }).collect::<Vec<CameraView>>();
+
+        cameras.sort_by_key(|c| c.order);
+        cameras
+    }
+
+    /// Upload the meshes modified since the previous frame.
+    fn upload_meshes(&self, world_state: &mut EntityComponentManager) {
+        for e in <(Mesh2D,)>::query_from(&world_state) {
+            let mesh = world_state.get_component_mut::<Mesh2D>(&e).unwrap();
+
+            if mesh.is_dirty() {
+                mesh.upload();
+            }
+        }
+    }
+
+    /// Create the vertex arrays of the meshes not drawn in the current context yet.
+    fn prepare_meshes(&self, world_state: &mut EntityComponentManager) {
+        for e in <(Mesh2D,)>::query_from(&world_state) {
+            world_state.get_component_mut::<Mesh2D>(&e).unwrap().prepare_context();
+        }
+    }
+
+    /// Draw all meshes, sorted by shader then material so each
+    /// shader and material is applied once.
+    /// # Arguments
+    /// * `view_projection` - The matrix uploaded to the `view_projection` uniform of each shader.
+    /// * `errors` - Where the uniform errors go.
+    fn draw_meshes(&self, world_state: &EntityComponentManager, view_projection: &Matrix4<f32>, errors: &mut Vec<ShaderError>) {
+        let mut meshes = <(Mesh2D,)>::query_from(&world_state).into_iter()
+            .map(|e| (world_state.get_component::<Mesh2D>(&e).unwrap(), e))
+            .collect::<Vec<(&Mesh2D, Entity)>>();
+
+        meshes.sort_by_key(|(m, _)| (m.material.get_shader().get_program(), Rc::as_ptr(&m.material)));
+
+        let mut current_shader = None;
+        let mut current_material = None;
+
+        for (mesh, entity) in meshes {
+            let material = &mesh.material;
+            let shader = material.get_shader();
+
+            if current_material != Some(Rc::as_ptr(material)) {
+                errors.extend(material.apply().err());
+                current_material = Some(Rc::as_ptr(material));
+            }
+
+            if current_shader != Some(shader.get_program()) {
+                errors.extend(shader.set_uniform_if_active("view_projection", *view_projection).err());
+                current_shader = Some(shader.get_program());
+            }
+
+            let model = match world_state.get_component::<Transform2D>(&entity) {
+                Some(transform) => transform.get_matrix(),
+                None => Matrix4::identity(),
+            };
+
+            errors.extend(shader.set_uniform_if_active("model", model).err());
+            mesh.draw();
+        }
+    }
+
+    /// Draw all meshes and sprites into a window.
+    /// # Arguments
+    /// * `window` - The window entity, its context must be current.
+    /// * `view_projection` - The matrix uploaded to the `view_projection` uniform of each shader.
+    /// * `sprites` - The sprites to draw.
+    /// * `errors` - Where the uniform errors go.
+    fn draw(&self, world_state: &mut EntityComponentManager, window: Entity, view_projection: &Matrix4<f32>, sprites: &[SpriteGroup], errors: &mut Vec<ShaderError>) {
+        self.draw_meshes(world_state, view_projection, errors);
+
+        let batch = world_state.get_component_mut::<SpriteBatch>(&window).unwrap();
+
+        for group in sprites {
+            for quad in group.quads.iter() {
+                batch.push(*quad);
+            }
+
+            group.shader.use_it();
+            errors.extend(group.shader.set_uniform_if_active("view_projection", *view_projection).err());
+            batch.flush(&group.shader, &group.texture);
+        }
+    }
+
+    /// Draw a window with all the cameras that target it.
+    /// # Arguments
+    /// * `window` - The window entity.
+    /// * `sprites` - The sprites to draw.
+    /// * `errors` - Where the uniform errors go.
+    fn draw_window(&self, world_state: &mut EntityComponentManager, window: Entity, sprites: &[SpriteGroup], errors: &mut Vec<ShaderError>) {
+        let (target_size, primary) = {
+            let window_component = world_state.get_component_mut::<Window>(&window).unwrap();
+
+            if window_component.raw.should_close() {
+                return
+            }
+
+            window_component.make_current();
+            (window_component.framebuffer_size, window_component.primary)
+        };
+
+        // The objects dropped while another window was current are deleted in their own context.
+        object::delete_pending();
+        self.prepare_meshes(world_state);
+
+        if world_state.get_component::<SpriteBatch>(&window).is_none() {
+            world_state.add_component(&window, SpriteBatch::new());
+        }
+
+        let cameras = self.collect_cameras(world_state, window, primary, target_size);
+
+        // Without camera everything is drawn directly in clip space, in the primary window.
+        if cameras.is_empty() {
+            if primary {
+                self.draw(world_state, window, &Matrix4::identity(), sprites, errors);
+            }
+
+            return
+        }
+
+        for camera in cameras.iter() {
+            let Rect { x, y, w, h } = camera.viewport;
+            let Color(r, g, b, a) = camera.clear_color;
+
+            unsafe {
+                gl::Viewport(x as i32, y as i32, w as i32, h as i32);
+                gl::Scissor(x as i32, y as i32, w as i32, h as i32);
+                gl::Enable(gl::SCISSOR_TEST);
+                gl::ClearColor(r, g, b, a);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                gl::Disable(gl::SCISSOR_TEST);
+            }
+
+            self.draw(world_state, window, &camera.view_projection, sprites, errors);
+        }
+
+        unsafe { gl::Viewport(0, 0, target_size.0 as i32, target_size.1 as i32) };
+    }
+}
+
 impl Sys for RenderSys {
 
     fn on_update(&self, world_state: &mut EntityComponentManager) {
-        for entity in <(Mesh2D,)>::query_from(&world_state) {
-            let m = world_state.get_component_mut::<Mesh2D>(&entity).unwrap();
-            m.draw();
+        // The previous systems may have left the context of another window current.
+        if let Some(entity) = Window::find_primary(world_state) {
+            world_state.get_component_mut::<Window>(&entity).unwrap().make_current();
+        }
+
+        object::delete_pending();
+        self.upload_meshes(world_state);
+
+        let sprites = self.collect_sprites(world_state);
+        let mut errors = Vec::new();
+
+        for window in <(Window,)>::query_from(&world_state) {
+            self.draw_window(world_state, window, &sprites, &mut errors);
+        }
+
+        for error in errors {
+            RenderErrors::report_to(world_state, error);
+        }
+
+        // Give the context back to the primary window for the other systems.
+        if let Some(entity) = Window::find_primary(world_state) {
+            world_state.get_component_mut::<Window>(&entity).unwrap().make_current();
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,122 @@
+use std::{rc::Rc, time::Duration};
+
+use crate::core::{
+    gl::{shader::Shader, sprite_batch::SpriteVertex, vertex::{Vertex, VertexLayout}},
+    sys::Sys,
+    world::{EntityComponentManager, EntitySelector},
+};
+
+use crate::components::{
+    mesh_2d::Mesh2D,
+    shader_watcher::{ShaderReloaded, ShaderWatcher},
+    sprite::Sprite,
+};
+
+/// Reload the shaders when their source files change.
+///
+/// The shaders of the meshes and sprites are watched automatically,
+/// other shaders (compute shaders, ...) can be added with `ShaderWatcher::watch`.
+/// A shader that fails to compile, or that reads vertex attributes one of
+/// its meshes (or `SpriteVertex` for sprites) doesn't provide, keeps its
+/// current program. Every reload is
+/// sent as a `ShaderReloaded` event, with the error if it failed.
+///
+/// Must be registered after `WindowSys` and before `RenderSys`,
+/// the shaders are compiled in the primary window context.
+pub struct ShaderReloadSys {
+    interval: Duration,
+}
+
+impl ShaderReloadSys {
+
+    /// Create new `ShaderReloadSys`
+    /// # Arguments
+    /// * `interval` - The time between two checks of the files.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+        }
+    }
+}
+
+impl Default for ShaderReloadSys {
+    /// Check the files twice per second.
+    fn default() -> Self {
+        ShaderReloadSys::new(Duration::from_millis(500))
+    }
+}
+
+impl Sys for ShaderReloadSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, ShaderWatcher::new(self.interval));
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        world.clear_events::<ShaderReloaded>();
+
+        let watcher_entity = match <(ShaderWatcher,)>::query_first_from(world) {
+            Some(entity) => entity,
+            None => return,
+        };
+
+        if !world.get_component::<ShaderWatcher>(&watcher_entity).unwrap().should_poll() {
+            return
+        }
+
+        let mut shaders: Vec<Rc<Shader>> = Vec::new();
+
+        // The vertex layouts of the meshes and sprites of each shader, a reloaded shader must still accept them.
+        let mut layouts: Vec<(Rc<Shader>, VertexLayout)> = Vec::new();
+        let mut add_layout = |shader: &Rc<Shader>, layout: &VertexLayout| {
+            if !layouts.iter().any(|(s, l)| Rc::ptr_eq(s, shader) && l == layout) {
+                layouts.push((shader.clone(), layout.clone()));
+            }
+        };
+
+        for e in <(Mesh2D,)>::query_from(world) {
+            let mesh = world.get_component::<Mesh2D>(&e).unwrap();
+            let shader = mesh.material.get_shader();
+
+            add_layout(shader, mesh.get_layout());
+            shaders.push(shader.clone());
+        }
+
+        let sprite_layout = SpriteVertex::layout();
+
+        for e in <(Sprite,)>::query_from(world) {
+            let shader = &world.get_component::<Sprite>(&e).unwrap().shader;
+
+            add_layout(shader, &sprite_layout);
+            shaders.push(shader.clone());
+        }
+
+        let watcher = world.get_component_mut::<ShaderWatcher>(&watcher_entity).unwrap();
+
+        for shader in shaders.iter() {
+            watcher.watch(shader);
+        }
+
+        let mut reloaded = Vec::new();
+
+        for shader in watcher.poll() {
+            let shader_layouts = layouts.iter()
+                .filter(|(s, _)| Rc::ptr_eq(s, &shader))
+                .map(|(_, l)| l.clone())
+                .collect::<Vec<VertexLayout>>();
+
+            let error = shader.reload_compatible(&shader_layouts).err();
+
+            if error.is_none() {
+                watcher.refresh(&shader);
+            }
+
+            reloaded.push(ShaderReloaded { paths: shader.get_source_paths().clone(), error });
+        }
+
+        for e in reloaded {
+            world.send_event(e);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+
+use crate::core::{sys::Sys, world::{EntityComponentManager, EntitySelector}};
+
+use crate::components::{
+    sprite::Sprite,
+    sprite_animation::SpriteAnimation,
+    time::Time,
+};
+
+/// Advance every `SpriteAnimation` with the frame delta
+/// time and update the region of their `Sprite`.
+pub struct SpriteAnimationSys;
+
+impl Sys for SpriteAnimationSys {
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        let delta = match <(Time,)>::query_first_from(world) {
+            Some(entity) => world.get_component::<Time>(&entity).unwrap().delta,
+            None => return,
+        };
+
+        for entity in <(SpriteAnimation,)>::query_from(world) {
+            let animation = world.get_component_mut::<SpriteAnimation>(&entity).unwrap();
+            animation.advance(delta);
+
+            if let Some(region) = animation.current_region() {
+                if let Some(sprite) = world.get_component_mut::<Sprite>(&entity) {
+                    sprite.region = region;
+                }
+            }
+        }
+    }
+}
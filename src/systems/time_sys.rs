@@ -0,0 +1,25 @@
+
+use crate::core::{sys::Sys, world::{EntityComponentManager, EntitySelector}};
+
+use crate::components::{
+    time::Time,
+};
+
+/// Update the `Time` component once per frame.
+///
+/// Should be registered before any system that use the delta time.
+pub struct TimeSys;
+
+impl Sys for TimeSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        let e = world.create_entity().unwrap();
+        world.add_component(&e, Time::new());
+    }
+
+    fn on_update(&self, world: &mut EntityComponentManager) {
+        if let Some(entity) = <(Time,)>::query_first_from(&world) {
+            world.get_component_mut::<Time>(&entity).unwrap().tick();
+        }
+    }
+}
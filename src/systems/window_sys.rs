@@ -2,101 +2,313 @@
 extern crate gl;
 extern crate glfw;
 
-use crate::{components::smc::SystemManagerComponent, core::world::EntityComponentManager};
+use crate::core::world::EntityComponentManager;
 use self::glfw::{Action, Context, Key};
 
 use crate::core::{
-    sys::Sys, 
+    entity::Entity,
+    gl::{object, sprite_batch::SpriteBatch},
+    sys::Sys,
+    platform_event::PlatformEvent,
     world::{
         EntitySelector,
     }
 };
 use crate::components::{
-    window::Window,
+    mesh_2d::Mesh2D,
+    render_errors::RenderErrors,
+    window::{OpenWindow, Window, WindowClosed, WindowEvent, WindowResized},
+    window_config::{WindowConfig, WindowMode},
 };
 
+/// Create the windows and own their event loop.
+///
+/// This is the only system that poll GLFW events: once per frame
+/// the previous events are cleared, then every GLFW event is converted
+/// into a `PlatformEvent` readable by all systems registered after it.
+/// The systems registered before it read the events one frame late,
+/// so `WindowSys` should be the first system.
+///
+/// Each event is also sent as a `WindowEvent` that tell which window
+/// received it. More windows can be opened at any time by sending an
+/// `OpenWindow` event, they share their GL objects with the first window.
+///
+/// In replay mode the GLFW events are dropped instead, the input
+/// comes from an `InputReplaySys` registered after it.
 pub struct WindowSys {
-    title: &'static str,
-    size: (u32, u32),
+    /// The windows created on start, the first one is the primary window.
+    configs: Vec<WindowConfig>,
+
+    /// Drop the GLFW events, the input is replayed by another system.
+    replay: bool,
 }
 
 impl WindowSys {
 
-    pub fn new(title: &'static str, size: (u32, u32)) -> Self {
+    /// Create new `WindowSys` for a resizable window with vsync.
+    /// # Arguments
+    /// * `title` - The window title.
+    /// * `size` - The window size in screen coordinates.
+    pub fn new(title: &str, size: (u32, u32)) -> Self {
+        Self::from_config(WindowConfig::new(title, size))
+    }
+
+    /// Create new `WindowSys`
+    /// # Arguments
+    /// * `config` - How the primary window is created.
+    pub fn from_config(config: WindowConfig) -> Self {
         Self {
-            title,
-            size,
+            configs: vec![config],
+            replay: false,
         }
     }
 
-    fn process_events(&self, window_component: &mut Window) {
-        for (_, event) in glfw::flush_messages(&window_component.event) {
-            match event {
-                glfw::WindowEvent::FramebufferSize(width, height) => unsafe {
-                    gl::Viewport(0, 0, width, height);
-                },
-                glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
-                    window_component.raw.set_should_close(true);
-                }
-                _ => {}
-            }
-        }
+    /// Open another window on start.
+    /// # Arguments
+    /// * `config` - How the window is created.
+    pub fn with_window(mut self, config: WindowConfig) -> Self {
+        self.configs.push(config);
+        self
     }
 
-}
+    /// Set the key that close the primary window (`Escape` by default).
+    /// # Arguments
+    /// * `close_key` - The key, `None` to never close the window from the keyboard.
+    pub fn with_close_key(mut self, close_key: Option<Key>) -> Self {
+        self.configs[0].close_key = close_key;
+        self
+    }
 
-impl Sys for WindowSys {
-    
-    fn on_start(&self, world: &mut EntityComponentManager) {
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
+    /// Set the replay mode, off by default.
+    ///
+    /// GLFW is still polled so the windows stay responsive, but its events
+    /// are neither converted nor acted on: the close key and the resize of
+    /// the viewport are ignored and no `PlatformEvent` or `WindowEvent` is sent.
+    /// # Arguments
+    /// * `replay` - `true` when an `InputReplaySys` provides the input.
+    pub fn with_replay(mut self, replay: bool) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    /// Create a window and add it to the world.
+    ///
+    /// The first window initialize GLFW and load the OpenGL methods,
+    /// the next ones share the GL objects of an existing window.
+    /// # Arguments
+    /// * `config` - How the window is created.
+    fn open_window(&self, world: &mut EntityComponentManager, config: &WindowConfig) -> Entity {
+        let shared = <(Window,)>::query_first_from(&world);
 
-        glfw.window_hint(glfw::WindowHint::ContextVersion(4, 1));
+        let mut glfw = match shared {
+            Some(entity) => world.get_component::<Window>(&entity).unwrap().glfw.clone(),
+            None => glfw::init(glfw::FAIL_ON_ERRORS).unwrap(),
+        };
+
+        glfw.window_hint(glfw::WindowHint::ContextVersion(config.gl_version.0, config.gl_version.1));
         glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-        
+        glfw.window_hint(glfw::WindowHint::Resizable(config.resizable));
+        glfw.window_hint(glfw::WindowHint::Decorated(config.decorated));
+        glfw.window_hint(glfw::WindowHint::Samples(config.samples));
+
         #[cfg(target_os = "macos")]
         glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
 
-        let (mut window, events) = glfw
-            .create_window(self.size.0, self.size.1, self.title, glfw::WindowMode::Windowed)
-            .expect("Failed to create GLFW Window");
+        let (width, height) = config.size;
 
-        window.make_current();
-        window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
-
-        // Load OpenGL methods...
-        gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-        let window_component = Window {
-            size:   self.size,
-            title:  String::from(self.title),
-            event:  events,
-            raw:    window,
-            glfw:   glfw,
+        let created = match shared {
+            Some(entity) => {
+                let window = world.get_component::<Window>(&entity).unwrap();
+                window.raw.create_shared(width, height, &config.title, glfw::WindowMode::Windowed)
+            },
+            None => glfw.create_window(width, height, &config.title, glfw::WindowMode::Windowed),
         };
 
+        let (mut window, events) = created.expect("Failed to create GLFW Window");
+
+        window.make_current();
+        window.set_all_polling(true);
+
+        if shared.is_none() {
+            // Load OpenGL methods...
+            gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+            object::mark_render_thread();
+        }
+
+        if config.samples.is_some() {
+            unsafe { gl::Enable(gl::MULTISAMPLE) };
+        }
+
+        let mut window_component = Window::new(&config.title, window, events, glfw);
+        window_component.primary = shared.is_none();
+        window_component.close_key = config.close_key;
+        window_component.exit_on_close = config.exit_on_close;
+        window_component.set_swap_interval(config.swap_interval);
+        window_component.set_decorated(config.decorated);
+        window_component.set_cursor_mode(config.cursor);
+
+        if config.mode != WindowMode::Windowed {
+            window_component.set_mode(config.mode);
+        }
+
+        if let Some(icon) = &config.icon {
+            if let Err(e) = window_component.set_icon(icon) {
+                RenderErrors::report_to(world, format!("Failed to load the window icon '{}': {}", icon, e.message));
+            }
+        }
+
         let e = world.create_entity().unwrap();
         world.add_component(&e, window_component);
+        e
+    }
+
+    /// Make the context of the primary window current, so the GL
+    /// objects created by the other systems are attached to it.
+    fn make_primary_current(&self, world: &mut EntityComponentManager) {
+        if let Some(entity) = Window::find_primary(world) {
+            world.get_component_mut::<Window>(&entity).unwrap().make_current();
+        }
+    }
+
+    /// Convert the pending events of a window.
+    ///
+    /// Returns the platform events and the resize events of this frame.
+    /// # Arguments
+    /// * `entity` - The window entity.
+    /// * `window_component` - The window.
+    fn process_events(&self, entity: Entity, window_component: &mut Window) -> (Vec<PlatformEvent>, Vec<WindowResized>) {
+        let mut events = glfw::flush_messages(&window_component.event)
+            .filter_map(|(_, e)| PlatformEvent::from_glfw(e))
+            .collect::<Vec<PlatformEvent>>();
+
+        let mut resized = Vec::new();
+
+        for event in events.iter_mut() {
+            if let PlatformEvent::CursorMoved { ref mut x, ref mut y } = *event {
+                let (cx, cy) = window_component.confine_cursor(*x, *y);
+                *x = cx;
+                *y = cy;
+            }
+
+            if let Some(e) = window_component.apply_resize_event(entity, event) {
+                resized.push(e);
+            }
+
+            match *event {
+                PlatformEvent::FramebufferResized { width, height } => unsafe {
+                    window_component.make_current();
+                    gl::Viewport(0, 0, width as i32, height as i32);
+                },
+                PlatformEvent::Key { key, action: Action::Press, .. } if Some(key) == window_component.close_key => {
+                    window_component.raw.set_should_close(true);
+                },
+                _ => {}
+            }
+        }
+
+        (events, resized)
+    }
+
+}
+
+impl Sys for WindowSys {
+
+    fn on_start(&self, world: &mut EntityComponentManager) {
+        for config in self.configs.iter() {
+            self.open_window(world, config);
+        }
+
+        self.make_primary_current(world);
     }
 
     fn on_update(&self, world: &mut EntityComponentManager) {
-        match <(Window,)>::query_first_from(&world) {
-            Some(entity) => {
-                let mut window_component = world.get_component_mut::<Window>(&entity).unwrap();
+        world.clear_events::<PlatformEvent>();
+        world.clear_events::<WindowEvent>();
+        world.clear_events::<WindowResized>();
+        world.clear_events::<WindowClosed>();
+
+        let requests = world.read_events::<OpenWindow>().to_vec();
+        world.clear_events::<OpenWindow>();
+
+        if !requests.is_empty() {
+            for OpenWindow(config) in requests {
+                self.open_window(world, &config);
+            }
+
+            self.make_primary_current(world);
+        }
+
+        let windows = <(Window,)>::query_from(&world);
+
+        // All windows share the same GLFW context, a single poll is enough.
+        match windows.first() {
+            Some(entity) => world.get_component_mut::<Window>(entity).unwrap().glfw.poll_events(),
+            None => return,
+        }
+
+        let mut exit = false;
+        let mut closed = Vec::new();
 
-                if !window_component.raw.should_close() {
-                    self.process_events(&mut window_component);
+        for entity in windows {
+            let window_component = world.get_component_mut::<Window>(&entity).unwrap();
+
+            if window_component.raw.should_close() {
+                if window_component.exit_on_close {
+                    exit = true;
+                    continue
+                }
+
+                // The other windows share the GL objects of the primary window, it is never
+                // destroyed alone: the close is refused and only the `CloseRequested` event is sent.
+                if window_component.primary {
+                    window_component.raw.set_should_close(false);
                 } else {
-                    match <(SystemManagerComponent,)>::query_first_from(&world) {
-                        Some(e) => {
-                            let mut smc = world.get_component_mut::<SystemManagerComponent>(&e).unwrap();
-                            smc.shutdown = true;
-                        },
-                        None => {},
-                    }
+                    closed.push(entity);
+                    continue
                 }
-            },
-            None => {},
+            }
+
+            if self.replay {
+                glfw::flush_messages(&window_component.event).for_each(drop);
+                continue
+            }
+
+            let (events, resized) = self.process_events(entity, window_component);
+
+            for e in events {
+                world.send_event(WindowEvent { window: entity, event: e.clone() });
+                world.send_event(e);
+            }
+
+            for e in resized {
+                world.send_event(e);
+            }
+        }
+
+        for entity in closed {
+            // Vertex arrays belong to the context of the window, delete them while it still exists.
+            world.get_component_mut::<Window>(&entity).unwrap().make_current();
+            world.remove_component::<SpriteBatch>(&entity);
+            object::delete_pending();
+
+            for mesh in <(Mesh2D,)>::query_from(&world) {
+                world.get_component_mut::<Mesh2D>(&mesh).unwrap().release_context();
+            }
+
+            world.destroy_entity(&entity);
+            world.send_event(WindowClosed { window: entity });
+        }
+
+        // A resize or a closed window changed the current context.
+        self.make_primary_current(world);
+
+        // The world can't live without any window.
+        if <(Window,)>::query_first_from(&world).is_none() {
+            exit = true;
+        }
+
+        if exit {
+            world.request_exit(0);
         }
     }
-}
\ No newline at end of file
+}